@@ -1,44 +1,93 @@
 #![feature(async_closure)]
 #![allow(incomplete_features)]
 #![feature(async_fn_in_trait)]
-#![feature(try_blocks)]
 use std::panic;
-use applet_framework::{Applet, applet_entrypoint, web_form};
-use applet_framework::dom::DomElement;
+use applet_framework::{Applet, AppletHandle, applet_entrypoint};
+use applet_framework::data::SelectedFile;
+use applet_framework::dom::{Children, Component, DomElement, ElementBuilder, MouseEvent, NodeId, Paragraph, RenderError, Renderer, Scope, State};
 
+use applet_framework::form::{HTMLForm, WebForm, ChoiceEnum, Choice, Text, File};
 
-#[wasm_bindgen]
-extern "C" {
-    fn alert(s: &str);
+#[derive(WebForm)]
+#[web_form(form = TestForm, submit = "HELLO!")]
+pub struct TestInput {
+    #[input(File, label = "Input file:", accept = ".xml")]
+    input_data: SelectedFile,
+    #[input(Text, label = "TEST2", value = "Test value")]
+    text: String,
+    #[input(Choice<Format>, label = "Format", default = Format::Plain)]
+    format: Format,
 }
 
-use applet_framework::form::{HTMLForm, Text, File, Submit};
-
-web_form!(TestForm(test_form) -> TestInput {
-    submit = "HELLO!".to_string(),
-    input_data = File {
-        accept: ".xml",
-        label: "Input file:".to_string(),
-        multiple: false
-    },
-    text = Text {
-        label: "TEST2".to_string(),
-        value: "Test value".to_string()
+#[derive(Copy, Clone, Eq, PartialEq, ChoiceEnum)]
+pub enum Format {
+    Plain,
+    #[choice(label = "Pretty-printed")]
+    PrettyPrinted,
+}
+
+/// Titled panel, collapsed by clicking its title
+pub struct Panel {
+    title: String
+}
+
+impl Component for Panel {
+    type State = bool;  // Whether the panel is collapsed
+
+    fn render(&self, collapsed: &State<bool>, children: &Children, _scope: &Scope, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let toggle = collapsed.clone();
+        let title = ElementBuilder::new("h2")
+            .text(&*self.title)
+            .on("click", move |_: MouseEvent| {
+                toggle.update(|collapsed| *collapsed = !*collapsed)
+                    .expect("applet must re-render");
+            });
+
+        let mut panel = ElementBuilder::new("section").child(title);
+        if !*collapsed.get() {
+            panel = panel.child(children);
+        }
+        panel.into_fragment(renderer)
     }
-});
+}
 
 applet_entrypoint!(TestApplet);
-pub struct TestApplet {}
+pub struct TestApplet {
+    submitted: Option<(String, u64, String, Format)>
+}
+
+pub enum TestMessage {
+    Submitted(TestInput)
+}
 
 impl Applet for TestApplet {
+    type Message = TestMessage;
+
     fn new() -> Self {
         panic::set_hook(Box::new(console_error_panic_hook::hook));
-        TestApplet {}
+        TestApplet { submitted: None }
     }
 
-    fn content(&self) -> Box<dyn DomElement> {
-        Box::new(TestForm::new(|input| {
-            alert(&*format!("Selected file is {} bytes", input.input_data.len()));
-        }))
+    fn update(&mut self, message: Self::Message) -> bool {
+        match message {
+            TestMessage::Submitted(input) => self.submitted = Some((input.input_data.name, input.input_data.size, input.text, input.format))
+        }
+        true
     }
-}
\ No newline at end of file
+
+    fn content(&self, handle: &AppletHandle<Self>) -> Box<dyn DomElement> {
+        let scope = handle.scope();
+        let mut content: Vec<Box<dyn DomElement>> = vec![
+            Box::new(scope.component(Panel { title: "Input".into() })
+                .child(TestForm::with_applet(handle, |handle, input| {
+                    handle.send(TestMessage::Submitted(input))
+                        .expect("applet must re-render");
+                })))
+        ];
+        if let Some((name, size, text, format)) = &self.submitted {
+            content.push(Box::new(scope.component(Panel { title: "Output".into() })
+                .child(Paragraph { text: format!("Selected file {} is {} bytes, text was \"{}\", format was {}", name, size, text, format.label()) })));
+        }
+        Box::new(content)
+    }
+}
@@ -0,0 +1,406 @@
+//! Derive macros for applet-framework, re-exported by the framework itself
+//!
+//! Generated code refers to the framework as `::applet_framework`
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Error, Expr, Fields, GenericArgument, Ident, LitStr, PathArguments, Token, Type};
+use syn::parse::ParseStream;
+
+/// Derives a form struct for the annotated form data struct
+///
+/// In format of:
+/// <pre>
+/// #[derive(WebForm)]
+/// #[web_form(form = FormStruct, id = form_element_id, submit = "Submit button text")]
+/// pub struct FormDataStruct {
+///     #[input(InputType, input_field = value, ...)]
+///     pub input_element_id: OutputType,
+///     ...
+/// }
+/// </pre>
+///
+/// `id` defaults to the snake_case form struct name, and `submit` to "Submit".
+/// Input field values are converted with `Into::into`, so string literals may be used for String fields and plain values for Option fields.
+/// Fields of `Optional<InputType>` inputs are those of the wrapped input, which may alternatively be passed whole as `input = InputType { ... }`.
+/// The form struct has the same visibility and generics as the form data struct, and implements the same traits as one created by the web_form! macro.
+#[proc_macro_derive(WebForm, attributes(web_form, input))]
+pub fn derive_web_form(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_web_form(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `ChoiceEnum` for a fieldless enum, for use with the `Choice` input
+///
+/// In format of:
+/// <pre>
+/// #[derive(Copy, Clone, Eq, PartialEq, ChoiceEnum)]
+/// pub enum EnumName {
+///     #[choice(label = "Displayed label", value = "submitted_value", group = "Option group")]
+///     Variant,
+///     ...
+/// }
+/// </pre>
+///
+/// `value` defaults to the snake_case variant name, and `label` to the variant name. Variants without `group` are not grouped.
+#[proc_macro_derive(ChoiceEnum, attributes(choice))]
+pub fn derive_choice_enum(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_choice_enum(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Struct-level #[web_form(...)] attribute
+struct FormAttribute {
+    form: Ident,
+    id: Option<Ident>,
+    submit: Option<Expr>,
+}
+
+/// Field-level #[input(...)] attribute
+struct InputAttribute {
+    input: Type,
+    fields: Vec<(Ident, Expr)>,
+}
+
+fn parse_form_attribute(attributes: &[Attribute]) -> syn::Result<FormAttribute> {
+    let attribute = attributes.iter()
+        .find(|attribute| attribute.path().is_ident("web_form"))
+        .ok_or_else(|| Error::new(Span::call_site(), "missing #[web_form(form = FormStruct)] attribute"))?;
+
+    let mut form = None;
+    let mut id = None;
+    let mut submit = None;
+    attribute.parse_nested_meta(|meta| {
+        if meta.path.is_ident("form") {
+            form = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("id") {
+            id = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("submit") {
+            submit = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `form`, `id` or `submit`"));
+        }
+        Ok(())
+    })?;
+
+    Ok(FormAttribute {
+        form: form.ok_or_else(|| Error::new_spanned(attribute, "missing `form = FormStruct`"))?,
+        id,
+        submit,
+    })
+}
+
+fn parse_input_attribute(field_name: &Ident, attributes: &[Attribute]) -> syn::Result<InputAttribute> {
+    let attribute = attributes.iter()
+        .find(|attribute| attribute.path().is_ident("input"))
+        .ok_or_else(|| Error::new_spanned(field_name, "missing #[input(InputType, ...)] attribute"))?;
+
+    attribute.parse_args_with(|stream: ParseStream| {
+        let input = stream.parse()?;
+        let mut fields = Vec::new();
+        while !stream.is_empty() {
+            stream.parse::<Token![,]>()?;
+            if stream.is_empty() {
+                break;
+            }
+            let name = stream.parse()?;
+            stream.parse::<Token![=]>()?;
+            fields.push((name, stream.parse()?));
+        }
+        Ok(InputAttribute { input, fields })
+    })
+}
+
+/// Variant-level #[choice(...)] attribute
+struct ChoiceAttribute {
+    value: String,
+    label: String,
+    group: Option<String>,
+}
+
+fn parse_choice_attribute(variant_name: &Ident, attributes: &[Attribute]) -> syn::Result<ChoiceAttribute> {
+    let mut value = to_snake_case(&variant_name.to_string());
+    let mut label = variant_name.to_string();
+    let mut group = None;
+    for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("choice")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("value") {
+                value = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("label") {
+                label = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("group") {
+                group = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `value`, `label` or `group`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(ChoiceAttribute { value, label, group })
+}
+
+/// Adds a turbofish to generic input types, as `Input<T> { ... }` is not a valid struct expression
+fn to_expression_path(input_type: &Type) -> Type {
+    let mut input_type = input_type.clone();
+    if let Type::Path(path) = &mut input_type {
+        for segment in &mut path.path.segments {
+            if let PathArguments::AngleBracketed(arguments) = &mut segment.arguments {
+                arguments.colon2_token = Some(Default::default());
+            }
+        }
+    }
+    input_type
+}
+
+/// Input wrapped by an `Optional<I>` input type, None for other input types
+fn optional_input(input_type: &Type) -> Option<&Type> {
+    let Type::Path(path) = input_type else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if segment.ident == "Optional" && arguments.args.len() == 1 => match &arguments.args[0] {
+            GenericArgument::Type(wrapped_type) => Some(wrapped_type),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+/// Converts CamelCase to snake_case, used for the default form id and option values. Acronyms such as "HTTP" are kept as one word
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (index, char) in chars.iter().enumerate() {
+        if char.is_uppercase() {
+            // Runs of capitals such as acronyms form one word, which ends before a capital followed by lowercase
+            let previous = index.checked_sub(1).map(|previous| chars[previous]);
+            let next = chars.get(index + 1);
+            let starts_word = match previous {
+                Some(previous) if previous.is_uppercase() => matches!(next, Some(next) if next.is_lowercase()),
+                Some(_) => true,
+                None => false
+            };
+            if starts_word {
+                snake_case.push('_');
+            }
+            snake_case.extend(char.to_lowercase());
+        } else {
+            snake_case.push(*char);
+        }
+    }
+    snake_case
+}
+
+fn expand_web_form(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "WebForm requires a struct with at least one named field"))
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "WebForm can only be derived for structs"))
+    };
+
+    let attribute = parse_form_attribute(&input.attrs)?;
+    let form_name = &attribute.form;
+    let form_id = attribute.id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| to_snake_case(&form_name.to_string()));
+    let submit_value = attribute.submit
+        .map(|submit| quote!(::core::convert::Into::into(#submit)))
+        .unwrap_or_else(|| quote!(::std::string::String::from("Submit")));
+
+    let result_name = &input.ident;
+    let visibility = &input.vis;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut input_ids = Vec::new();
+    let mut input_types = Vec::new();
+    let mut input_docs = Vec::new();
+    let mut input_constructors = Vec::new();
+    let mut where_predicates = where_clause
+        .map(|where_clause| where_clause.predicates.iter().map(|predicate| quote!(#predicate)).collect())
+        .unwrap_or_else(Vec::new);
+    for field in fields {
+        let input_id = field.ident.as_ref().expect("named fields must have an identifier");
+        let InputAttribute { input: input_type, fields: input_fields } = parse_input_attribute(input_id, &field.attrs)?;
+        let output_type = &field.ty;
+        let (field_names, field_values): (Vec<_>, Vec<_>) = input_fields.into_iter().unzip();
+
+        where_predicates.push(quote!(#input_type: ::applet_framework::form::FormInput<Output = #output_type>));
+        input_docs.push(field.attrs.iter().filter(|attribute| attribute.path().is_ident("doc")).collect::<Vec<_>>());
+        let input_path = to_expression_path(&input_type);
+        let constructor = match optional_input(&input_type) {
+            // The wrapped input may be passed whole, its form and name are replaced by Optional::new
+            Some(_) if field_names.len() == 1 && field_names[0] == "input" => {
+                let input = &field_values[0];
+                quote!(#input_path::new(#form_id, stringify!(#input_id), ::core::convert::Into::into(#input)))
+            }
+            Some(wrapped_type) => {
+                let wrapped_path = to_expression_path(wrapped_type);
+                quote! {
+                    #input_path::new(#form_id, stringify!(#input_id), #wrapped_path {
+                        form: #form_id,
+                        name: stringify!(#input_id),
+                        #(#field_names: ::core::convert::Into::into(#field_values)),*
+                    })
+                }
+            }
+            None => quote! {
+                #input_path {
+                    form: #form_id,
+                    name: stringify!(#input_id),
+                    #(#field_names: ::core::convert::Into::into(#field_values)),*
+                }
+            }
+        };
+        input_constructors.push(quote!(#input_id: #constructor));
+        input_ids.push(input_id);
+        input_types.push(input_type);
+    }
+
+    let form_doc = format!("Form for [`{}`], generated by derive(WebForm)", result_name);
+    let option_ids: Vec<_> = input_ids.iter().map(|id| format_ident!("__{}", id)).collect();
+
+    Ok(quote! {
+        #[doc = #form_doc]
+        #visibility struct #form_name #impl_generics where #(#where_predicates),* {
+            #(#(#input_docs)* #input_ids: #input_types,)*
+            submit: ::applet_framework::form::Submit,
+            on_submit_callback: ::applet_framework::form::SubmitCallback<#result_name #type_generics>,
+            live_mode: ::core::option::Option<::applet_framework::form::LiveMode<#result_name #type_generics>>
+        }
+
+        impl #impl_generics ::core::clone::Clone for #form_name #type_generics where #(#where_predicates),* {
+            fn clone(&self) -> Self {
+                #form_name {
+                    #(#input_ids: ::core::clone::Clone::clone(&self.#input_ids),)*
+                    submit: ::core::clone::Clone::clone(&self.submit),
+                    on_submit_callback: ::core::clone::Clone::clone(&self.on_submit_callback),
+                    live_mode: ::core::clone::Clone::clone(&self.live_mode)
+                }
+            }
+        }
+
+        impl #impl_generics ::applet_framework::dom::DomElement for #form_name #type_generics where #(#where_predicates),* {
+            fn to_nodes(&self, renderer: &mut dyn ::applet_framework::dom::Renderer) -> ::core::result::Result<::applet_framework::dom::NodeId, ::applet_framework::dom::RenderError> {
+                ::applet_framework::form::form_to_nodes(
+                    self,
+                    #form_id,
+                    &[#(&self.#input_ids as &dyn ::applet_framework::dom::DomElement,)* &self.submit],
+                    &self.on_submit_callback,
+                    renderer
+                )
+            }
+        }
+
+        impl #impl_generics ::applet_framework::form::HTMLForm for #form_name #type_generics where #(#where_predicates),* {
+            type Output = #result_name #type_generics;
+
+            async fn parse_form_data(&self, form_data: &dyn ::applet_framework::form::FormSource) -> ::core::result::Result<Self::Output, ::std::vec::Vec<::applet_framework::form::FormError>> {
+                let mut errors = ::std::vec::Vec::new();
+                #(
+                    let #option_ids = match ::applet_framework::form::FormInput::parse(&self.#input_ids, ::applet_framework::form::FormInput::form_value(&self.#input_ids, form_data, stringify!(#input_ids))).await {
+                        ::core::result::Result::Ok(value) => ::core::option::Option::Some(value),
+                        ::core::result::Result::Err(error) => {
+                            errors.push(error);
+                            ::core::option::Option::None
+                        }
+                    };
+                )*
+                if errors.is_empty() {
+                    ::core::result::Result::Ok(#result_name {
+                        #(#input_ids: #option_ids.expect("field without error must have been parsed")),*
+                    })
+                } else {
+                    ::core::result::Result::Err(errors)
+                }
+            }
+
+            fn submit_callback(&self) -> &::applet_framework::form::SubmitCallback<Self::Output> {
+                &self.on_submit_callback
+            }
+
+            fn live_mode(&self) -> ::core::option::Option<&::applet_framework::form::LiveMode<Self::Output>> {
+                ::core::option::Option::as_ref(&self.live_mode)
+            }
+
+            fn with_live_mode(self, live_mode: ::applet_framework::form::LiveMode<Self::Output>) -> Self {
+                #form_name { live_mode: ::core::option::Option::Some(live_mode), ..self }
+            }
+
+            fn with_callback(on_submit: ::applet_framework::form::SubmitCallback<Self::Output>) -> Self {
+                #form_name {
+                    submit: ::applet_framework::form::Submit {
+                        form: #form_id,
+                        name: "submit",
+                        value: #submit_value
+                    },
+                    on_submit_callback: on_submit,
+                    live_mode: ::core::option::Option::None,
+                    #(#input_constructors,)*
+                }
+            }
+        }
+    })
+}
+
+fn expand_choice_enum(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let variants = match &input.data {
+        Data::Enum(data) if !data.variants.is_empty() => &data.variants,
+        _ => return Err(Error::new_spanned(&input.ident, "ChoiceEnum can only be derived for enums with at least one variant"))
+    };
+
+    let mut variant_names = Vec::new();
+    let mut values = Vec::new();
+    let mut labels = Vec::new();
+    let mut groups = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "ChoiceEnum variants cannot have fields"));
+        }
+        let ChoiceAttribute { value, label, group } = parse_choice_attribute(&variant.ident, &variant.attrs)?;
+        if values.contains(&value) {
+            return Err(Error::new_spanned(variant, format!("duplicate choice value \"{}\"", value)));
+        }
+        variant_names.push(&variant.ident);
+        values.push(value);
+        labels.push(label);
+        groups.push(match group {
+            Some(group) => quote!(::core::option::Option::Some(#group)),
+            None => quote!(::core::option::Option::None)
+        });
+    }
+
+    let enum_name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::applet_framework::form::ChoiceEnum for #enum_name #type_generics #where_clause {
+            const VARIANTS: &'static [Self] = &[#(#enum_name::#variant_names),*];
+
+            fn value(self) -> &'static str {
+                match self {
+                    #(#enum_name::#variant_names => #values),*
+                }
+            }
+
+            fn label(self) -> &'static str {
+                match self {
+                    #(#enum_name::#variant_names => #labels),*
+                }
+            }
+
+            fn group(self) -> ::core::option::Option<&'static str> {
+                match self {
+                    #(#enum_name::#variant_names => #groups),*
+                }
+            }
+        }
+    })
+}
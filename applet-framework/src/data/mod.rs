@@ -1,5 +1,11 @@
 //! Various data types
 
+use std::rc::Rc;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use js_sys::{ArrayBuffer, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
 /// Simple 3-channel color, supporting only RGB with no transparency
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub struct Color3 {
@@ -43,4 +49,348 @@ impl Color3 {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// Email address, validated to have a local part and a domain name
+///
+/// Validation follows the browser's rules for &lt;input type='email'&gt;, and does not check whether the address exists.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct EmailAddress(String);
+
+impl EmailAddress {
+    /// Parses an email address in the form of "local@domain"
+    pub fn parse(string: &str) -> Option<Self> {
+        let (local, domain) = string.split_once('@')?;
+        let valid_local = !local.is_empty() && local.chars().all(|char| char.is_ascii_alphanumeric() || ".!#$%&'*+/=?^_`{|}~-".contains(char));
+        let valid_domain = domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|char| char.is_ascii_alphanumeric() || char == '-')
+        });
+        if valid_local && valid_domain {
+            Some(EmailAddress(string.to_string()))
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Part of the address before the '@'
+    pub fn local_part(&self) -> &str {
+        self.0.split_once('@').expect("email address must contain '@'").0
+    }
+
+    /// Domain name of the address
+    pub fn domain(&self) -> &str {
+        self.0.split_once('@').expect("email address must contain '@'").1
+    }
+}
+
+impl std::fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Absolute URL, validated to have an allowed scheme
+///
+/// Only the scheme is validated, the rest of the URL is only checked to be non-empty and free of whitespace.
+/// Schemes such as "javascript:" and "data:" run or embed content when the URL is used as a link, so only "http:" and "https:" are allowed by default.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct Url(String);
+
+impl Url {
+    /// Schemes allowed by [`Url::parse`]
+    pub const DEFAULT_SCHEMES: [&'static str; 2] = ["http", "https"];
+
+    /// Parses an absolute http or https URL, such as "https://example.com/"
+    pub fn parse(string: &str) -> Option<Self> {
+        Self::parse_with_schemes(string, &Self::DEFAULT_SCHEMES)
+    }
+
+    /// Parses an absolute URL with one of the specified schemes, which are compared case-insensitively
+    ///
+    /// # Arguments
+    ///
+    /// * `string`: URL to parse
+    /// * `schemes`: Allowed schemes, without the ':'
+    ///
+    /// returns: Option<Url>, None if the URL is invalid or its scheme is not allowed
+    pub fn parse_with_schemes(string: &str, schemes: &[&str]) -> Option<Self> {
+        let (scheme, rest) = string.split_once(':')?;
+        let valid_scheme = scheme.starts_with(|char: char| char.is_ascii_alphabetic())
+            && scheme.chars().all(|char| char.is_ascii_alphanumeric() || "+-.".contains(char))
+            && schemes.iter().any(|allowed| allowed.eq_ignore_ascii_case(scheme));
+        if valid_scheme && !rest.is_empty() && !string.contains(char::is_whitespace) {
+            Some(Url(string.to_string()))
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Scheme of the URL, without the ':'
+    pub fn scheme(&self) -> &str {
+        self.0.split_once(':').expect("url must contain scheme").0
+    }
+}
+
+impl std::fmt::Display for Url {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Month of a year, without a day
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+pub struct YearMonth {
+    pub year: i32,
+    /// Month, from 1 to 12
+    pub month: u32,
+}
+
+impl YearMonth {
+    /// Creates a year-month, None if the month is not between 1 and 12 or the year is out of range
+    pub fn new(year: i32, month: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(|_| YearMonth { year, month })
+    }
+
+    /// Parses a year-month in the form of "YYYY-MM"
+    pub fn parse(string: &str) -> Option<Self> {
+        let (year, month) = string.rsplit_once('-')?;
+        if month.len() != 2 {
+            return None;
+        }
+        YearMonth::new(year.parse().ok()?, month.parse().ok()?)
+    }
+
+    /// First day of the month, None if the year or month is out of range
+    pub fn first_day(self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1)
+    }
+}
+
+impl std::fmt::Display for YearMonth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+/// File selected through a file input, including its metadata
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SelectedFile {
+    /// Name of the file, without path
+    pub name: String,
+    /// MIME type as reported by the browser, empty if unknown
+    pub mime_type: String,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Last-modified time of the file, if the browser provides a valid one
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Contents of the file
+    pub bytes: Box<[u8]>,
+}
+
+/// Handle to a file submitted through a file input
+///
+/// Either a browser file, or an in-memory file used to simulate file selection outside of a browser.
+/// Cloning the handle does not copy the file's contents.
+#[derive(Debug, Clone)]
+pub struct FileHandle {
+    source: FileSource
+}
+
+#[derive(Debug, Clone)]
+enum FileSource {
+    Browser(web_sys::File),
+    Memory {
+        name: String,
+        mime_type: String,
+        last_modified: Option<DateTime<Utc>>,
+        bytes: Rc<[u8]>,
+    },
+}
+
+impl FileHandle {
+    /// Creates an in-memory file
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: Name of the file, without path
+    /// * `mime_type`: MIME type of the file, may be empty if unknown
+    /// * `bytes`: Contents of the file
+    ///
+    /// returns: FileHandle
+    pub fn from_bytes(name: impl Into<String>, mime_type: impl Into<String>, bytes: impl Into<Rc<[u8]>>) -> Self {
+        FileHandle {
+            source: FileSource::Memory {
+                name: name.into(),
+                mime_type: mime_type.into(),
+                last_modified: None,
+                bytes: bytes.into(),
+            }
+        }
+    }
+
+    /// Sets the last-modified time of an in-memory file, browser files are returned unchanged
+    pub fn with_last_modified(mut self, time: DateTime<Utc>) -> Self {
+        if let FileSource::Memory { last_modified, .. } = &mut self.source {
+            *last_modified = Some(time);
+        }
+        self
+    }
+
+    /// Name of the file, without path
+    pub fn name(&self) -> String {
+        match &self.source {
+            FileSource::Browser(file) => file.name(),
+            FileSource::Memory { name, .. } => name.clone()
+        }
+    }
+
+    /// MIME type of the file, empty if unknown
+    pub fn mime_type(&self) -> String {
+        match &self.source {
+            FileSource::Browser(file) => file.type_(),
+            FileSource::Memory { mime_type, .. } => mime_type.clone()
+        }
+    }
+
+    /// Size of the file in bytes
+    pub fn size(&self) -> u64 {
+        match &self.source {
+            FileSource::Browser(file) => file.size() as u64,
+            FileSource::Memory { bytes, .. } => bytes.len() as u64
+        }
+    }
+
+    /// Last-modified time of the file, if known and valid
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        match &self.source {
+            FileSource::Browser(file) => Utc.timestamp_millis_opt(file.last_modified() as i64).single(),
+            FileSource::Memory { last_modified, .. } => *last_modified
+        }
+    }
+
+    /// Whether this is the unnamed, empty file browsers submit if no file was selected
+    pub fn is_unselected(&self) -> bool {
+        self.name().is_empty() && self.size() == 0
+    }
+
+    /// Reads the entire file
+    pub async fn read(&self) -> Result<Box<[u8]>, JsValue> {
+        match &self.source {
+            FileSource::Browser(file) => read_blob(file).await,
+            FileSource::Memory { bytes, .. } => Ok(Box::from(&**bytes))
+        }
+    }
+
+    /// Reads a range of bytes from the file, the range is clamped to the size of the file
+    ///
+    /// # Arguments
+    ///
+    /// * `start`: Offset of the first byte to read
+    /// * `end`: Offset after the last byte to read
+    ///
+    /// returns: Result<Box<[u8]>, JsValue>
+    pub async fn read_range(&self, start: u64, end: u64) -> Result<Box<[u8]>, JsValue> {
+        let end = u64::min(end, self.size());
+        let start = u64::min(start, end);
+        match &self.source {
+            FileSource::Browser(file) => read_blob(&file.slice_with_f64_and_f64(start as f64, end as f64)?).await,
+            FileSource::Memory { bytes, .. } => Ok(Box::from(&bytes[start as usize..end as usize]))
+        }
+    }
+}
+
+impl From<web_sys::File> for FileHandle {
+    fn from(file: web_sys::File) -> Self {
+        FileHandle { source: FileSource::Browser(file) }
+    }
+}
+
+/// Reads the contents of a browser Blob
+async fn read_blob(blob: &web_sys::Blob) -> Result<Box<[u8]>, JsValue> {
+    let buff: ArrayBuffer = JsFuture::from(blob.array_buffer())
+        .await?
+        .dyn_into()
+        .expect("array_buffer() must return array buffer");
+
+    Ok(Uint8Array::new(&buff).to_vec().into_boxed_slice())
+}
+
+/// File selected through a file input, read in chunks rather than all at once
+///
+/// Only the current chunk is held in memory, allowing files larger than available memory to be processed.
+pub struct ChunkedFile {
+    /// Name of the file, without path
+    pub name: String,
+    /// MIME type as reported by the browser, empty if unknown
+    pub mime_type: String,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Last-modified time of the file, if the browser provides a valid one
+    pub last_modified: Option<DateTime<Utc>>,
+    file: FileHandle,
+    chunk_size: u64,
+    position: u64,
+}
+
+impl ChunkedFile {
+    /// Wraps a file into a chunked reader
+    ///
+    /// # Arguments
+    ///
+    /// * `file`: File to read
+    /// * `chunk_size`: Maximum size of each chunk in bytes, at least 1
+    ///
+    /// returns: ChunkedFile
+    pub fn new(file: FileHandle, chunk_size: usize) -> Self {
+        ChunkedFile {
+            name: file.name(),
+            mime_type: file.mime_type(),
+            size: file.size(),
+            last_modified: file.last_modified(),
+            file,
+            chunk_size: chunk_size.max(1) as u64,
+            position: 0,
+        }
+    }
+
+    /// Number of bytes read so far
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Number of bytes not yet read
+    pub fn remaining(&self) -> u64 {
+        self.size - self.position
+    }
+
+    /// Reads the next chunk of the file
+    ///
+    /// returns: Result<Option<Box<[u8]>>, JsValue>, yielding None once the entire file has been read
+    pub async fn next_chunk(&mut self) -> Result<Option<Box<[u8]>>, JsValue> {
+        if self.position >= self.size {
+            return Ok(None);
+        }
+        let end = u64::min(self.position + self.chunk_size, self.size);
+        let chunk = self.file.read_range(self.position, end).await?;
+
+        self.position = end;
+        Ok(Some(chunk))
+    }
+
+    /// Restarts reading from the start of the file
+    pub fn rewind(&mut self) {
+        self.position = 0;
+    }
+}
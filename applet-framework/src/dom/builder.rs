@@ -0,0 +1,206 @@
+//! Declarative element builder, for implementing [`DomElement`] without chains of [`Renderer`] calls
+//!
+//! In format of:
+//! <pre>
+//! ElementBuilder::new("div")
+//!     .class("form-group")
+//!     .child(ElementBuilder::new("label").attribute("for", id).text(label))
+//!     .child(ElementBuilder::new("input").attribute("type", "text").boolean_attribute("required", true))
+//!     .on("click", |event: MouseEvent| { ... })
+//!     .into_fragment(renderer)
+//! </pre>
+
+use wasm_bindgen::JsCast;
+use crate::dom::{DomElement, Listener, NodeId, RenderError, Renderer, listener};
+use crate::dom::vdom::KEY_ATTRIBUTE;
+
+/// Child node of an [`ElementBuilder`]
+pub enum Child<'a> {
+    /// Element, built along with its parent
+    Element(ElementBuilder<'a>),
+    /// Text node, the text is not interpreted as HTML
+    Text(String),
+    /// Nodes of another DOM element, created along with the parent
+    Nodes(&'a dyn DomElement),
+    /// Node that was already created with the same renderer
+    Rendered(NodeId),
+}
+
+impl<'a> From<ElementBuilder<'a>> for Child<'a> {
+    fn from(element: ElementBuilder<'a>) -> Self {
+        Child::Element(element)
+    }
+}
+
+impl<'a, T: DomElement> From<&'a T> for Child<'a> {
+    fn from(element: &'a T) -> Self {
+        Child::Nodes(element)
+    }
+}
+
+impl<'a> From<&'a dyn DomElement> for Child<'a> {
+    fn from(element: &'a dyn DomElement) -> Self {
+        Child::Nodes(element)
+    }
+}
+
+impl From<NodeId> for Child<'_> {
+    fn from(node: NodeId) -> Self {
+        Child::Rendered(node)
+    }
+}
+
+impl Child<'_> {
+    /// Creates this node, and any children it has
+    pub fn build(self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        match self {
+            Child::Element(element) => element.build(renderer),
+            Child::Text(text) => renderer.create_text(&text),
+            Child::Nodes(element) => element.to_nodes(renderer),
+            Child::Rendered(node) => Ok(node)
+        }
+    }
+}
+
+/// Builder for an element with attributes, classes, children and event listeners
+///
+/// Nothing is created until the builder is built with a [`Renderer`]
+pub struct ElementBuilder<'a> {
+    tag: String,
+    attributes: Vec<(String, String)>,
+    classes: Vec<String>,
+    children: Vec<Child<'a>>,
+    listeners: Vec<(String, Listener)>,
+}
+
+impl<'a> ElementBuilder<'a> {
+    /// Starts building an element with the specified tag name
+    pub fn new(tag: &str) -> Self {
+        ElementBuilder {
+            tag: tag.to_string(),
+            attributes: Vec::new(),
+            classes: Vec::new(),
+            children: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Sets an attribute, replacing any earlier value
+    pub fn attribute(mut self, name: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(attribute, _)| attribute == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.attributes.push((name.to_string(), value))
+        }
+        self
+    }
+
+    /// Sets an attribute if the value is Some
+    pub fn optional_attribute(self, name: &str, value: Option<impl Into<String>>) -> Self {
+        match value {
+            Some(value) => self.attribute(name, value),
+            None => self
+        }
+    }
+
+    /// Sets a boolean attribute such as `required` or `checked`, which is omitted if `present` is false
+    pub fn boolean_attribute(self, name: &str, present: bool) -> Self {
+        if present {
+            self.attribute(name, "")
+        } else {
+            self
+        }
+    }
+
+    /// Sets the key used to match this element against the previous render, see [`vdom`](crate::dom::vdom)
+    ///
+    /// Keys only need to be unique among the element's siblings
+    pub fn key(self, key: impl Into<String>) -> Self {
+        self.attribute(KEY_ATTRIBUTE, key)
+    }
+
+    /// Adds a class, classes are combined into the `class` attribute
+    pub fn class(mut self, class: &str) -> Self {
+        self.classes.push(class.to_string());
+        self
+    }
+
+    /// Appends a text node
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.children.push(Child::Text(text.into()));
+        self
+    }
+
+    /// Appends a child node
+    pub fn child(mut self, child: impl Into<Child<'a>>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    /// Appends child nodes in order
+    pub fn children<C: Into<Child<'a>>>(mut self, children: impl IntoIterator<Item=C>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds an event listener, the element is the event's `currentTarget`
+    ///
+    /// The listener is removed when the element is removed by a [`VirtualDom`](crate::dom::vdom::VirtualDom) or its renderer is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `event`: Name of the event, e.g. "click" or "keydown"
+    /// * `handler`: Function called for each event, receiving it as [`Event`] or a specific event type such as [`KeyboardEvent`]
+    ///
+    /// [`Event`]: crate::dom::Event
+    /// [`KeyboardEvent`]: crate::dom::KeyboardEvent
+    pub fn on<E: JsCast>(mut self, event: &str, handler: impl FnMut(E) + 'static) -> Self {
+        self.listeners.push((event.to_string(), listener(handler)));
+        self
+    }
+
+    /// Creates the element and its children
+    ///
+    /// returns: Result<NodeId, RenderError>, the created element
+    pub fn build(self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let element = renderer.create_element(&self.tag)?;
+        for (name, value) in &self.attributes {
+            renderer.set_attribute(element, name, value)?;
+        }
+        if !self.classes.is_empty() {
+            renderer.set_attribute(element, "class", &self.classes.join(" "))?;
+        }
+        for child in self.children {
+            let child = child.build(renderer)?;
+            renderer.append_child(element, child)?;
+        }
+        for (event, listener) in self.listeners {
+            renderer.add_listener(element, &event, listener)?;
+        }
+        Ok(element)
+    }
+
+    /// Creates the element and its children, collected into a document fragment as returned by [`DomElement::to_nodes`]
+    ///
+    /// returns: Result<NodeId, RenderError>, the created fragment
+    pub fn into_fragment(self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        fragment(renderer, [self])
+    }
+}
+
+/// Creates nodes and collects them into a document fragment
+///
+/// # Arguments
+///
+/// * `renderer`: Renderer to create the nodes with
+/// * `children`: Nodes to create, in order
+///
+/// returns: Result<NodeId, RenderError>, the created fragment
+pub fn fragment<'a, C: Into<Child<'a>>>(renderer: &mut dyn Renderer, children: impl IntoIterator<Item=C>) -> Result<NodeId, RenderError> {
+    let fragment = renderer.create_fragment()?;
+    for child in children {
+        let child = child.into().build(renderer)?;
+        renderer.append_child(fragment, child)?;
+    }
+    Ok(fragment)
+}
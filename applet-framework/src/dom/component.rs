@@ -0,0 +1,291 @@
+//! Reusable components with properties, children and local state
+//!
+//! A component is a struct holding its properties, which renders into other elements, including nested components and forms.
+//! Components are created through a [`Scope`], which gives each component state that is kept between renders of the applet.
+//!
+//! In format of:
+//! <pre>
+//! struct Panel { title: String }
+//!
+//! impl Component for Panel {
+//!     type State = bool;  // Whether the panel is collapsed
+//!
+//!     fn render(&self, collapsed: &State&lt;bool&gt;, children: &Children, scope: &Scope, renderer: &mut dyn Renderer) -> Result&lt;NodeId, RenderError&gt; {
+//!         let toggle = collapsed.clone();
+//!         ElementBuilder::new("section")
+//!             .child(ElementBuilder::new("h2").text(&*self.title).on("click", move |_: MouseEvent| toggle.update(|collapsed| *collapsed = !*collapsed).expect("applet must re-render")))
+//!             .child(children)
+//!             .into_fragment(renderer)
+//!     }
+//! }
+//!
+//! handle.scope().component(Panel { title: "Statistics".into() })
+//!     .child(Paragraph { text: "...".into() })
+//! </pre>
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, Ref, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::rc::Rc;
+use wasm_bindgen::JsValue;
+use crate::dom::{DomElement, NodeId, RenderError, Renderer, fragment};
+
+/// Trait for components, implemented by a struct holding the component's properties
+pub trait Component: 'static {
+    /// Local state of the component, starting at its default value when the component is first rendered
+    type State: Default + 'static;
+
+    /// Create the nodes for this component, collected into a document fragment
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: This component's state, which may be cloned into event handlers to update it
+    /// * `children`: Children set by the parent, which the component places in its content
+    /// * `scope`: Scope for creating nested components
+    /// * `renderer`: Renderer to create the nodes with
+    ///
+    /// returns: Result<NodeId, RenderError>, the created fragment
+    fn render(&self, state: &State<Self::State>, children: &Children, scope: &Scope, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError>;
+}
+
+/// Child elements passed to a component
+#[derive(Default)]
+pub struct Children(Vec<Box<dyn DomElement>>);
+
+impl Children {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterates over the children, to place them separately
+    pub fn iter(&self) -> impl Iterator<Item=&dyn DomElement> {
+        self.0.iter().map(|child| &**child)
+    }
+}
+
+impl DomElement for Children {
+    /// Creates the nodes of every child in order, collected into a single fragment
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        fragment(renderer, self.iter())
+    }
+}
+
+/// Context in which components are created, identifying them to keep their state between renders
+///
+/// Components are identified by their position among the components created in the same scope, or by their key if set.
+/// The root scope of an applet is obtained through [`crate::AppletHandle::scope`], components receive the scope for their children in [`Component::render`].
+#[derive(Clone)]
+pub struct Scope {
+    store: Rc<StateStore>,
+    path: Rc<str>,
+    /// Number of components created in this scope during the current render
+    created: Rc<Cell<usize>>,
+}
+
+/// State of all components of an applet, with the state of components that are no longer rendered being dropped
+struct StateStore {
+    states: RefCell<HashMap<(String, TypeId), Rc<dyn Any>>>,
+    rendered: RefCell<HashSet<(String, TypeId)>>,
+    rerender: Option<Box<dyn Fn() -> Result<(), JsValue>>>,
+    /// Whether a render is in progress, during which updates do not re-render immediately
+    rendering: Cell<bool>,
+    /// Whether state was updated during the current render
+    updated_while_rendering: Cell<bool>,
+    /// Whether the current render was requested by updates during the previous render, which may only happen once in a row
+    follow_up: Cell<bool>,
+}
+
+impl StateStore {
+    fn state<C: Component>(self: &Rc<Self>, path: &str) -> State<C::State> {
+        let id = (path.to_string(), TypeId::of::<C>());
+        self.rendered.borrow_mut().insert(id.clone());
+        let value = self.states.borrow_mut()
+            .entry(id)
+            .or_insert_with(|| Rc::new(RefCell::new(C::State::default())))
+            .clone();
+        State {
+            value: value.downcast().expect("state must match the type of its component"),
+            store: self.clone(),
+        }
+    }
+}
+
+impl Scope {
+    /// Creates a root scope that is not attached to an applet, such as for rendering components in tests
+    ///
+    /// Updating state does not re-render anything, renders must be delimited with [`Scope::begin_render`] and [`Scope::end_render`] to keep state between them.
+    pub fn new() -> Self {
+        Self::with_store(None)
+    }
+
+    /// Creates a root scope that re-renders when the state of a component is updated, as the root scope of an applet does
+    ///
+    /// # Arguments
+    ///
+    /// * `rerender`: Function re-rendering the components of the scope, called when the state of a component is updated outside of a render
+    pub fn root(rerender: impl Fn() -> Result<(), JsValue> + 'static) -> Self {
+        Self::with_store(Some(Box::new(rerender)))
+    }
+
+    fn with_store(rerender: Option<Box<dyn Fn() -> Result<(), JsValue>>>) -> Self {
+        let store = StateStore {
+            states: RefCell::new(HashMap::new()),
+            rendered: RefCell::new(HashSet::new()),
+            rerender,
+            rendering: Cell::new(false),
+            updated_while_rendering: Cell::new(false),
+            follow_up: Cell::new(false),
+        };
+        Scope { store: Rc::new(store), path: Rc::from(""), created: Rc::new(Cell::new(0)) }
+    }
+
+    /// Starts a render of the applet, components are identified by their position from the start of the render
+    ///
+    /// Only used for root scopes, the applet's scope is managed by its [`crate::AppletHandle`]
+    pub fn begin_render(&self) {
+        self.created.set(0);
+        self.store.rendered.borrow_mut().clear();
+        self.store.rendering.set(true);
+    }
+
+    /// Completes a render of the applet, dropping the state of components that were not rendered
+    ///
+    /// If state was updated during the render, the scope is re-rendered once. Updates during that re-render do not re-render again,
+    /// so that components updating their state while rendering do not re-render indefinitely.
+    pub fn end_render(&self) {
+        {
+            let rendered = self.store.rendered.borrow();
+            self.store.states.borrow_mut().retain(|id, _| rendered.contains(id));
+        }
+        self.store.rendering.set(false);
+
+        let follow_up = self.store.updated_while_rendering.take() && !self.store.follow_up.get();
+        self.store.follow_up.set(follow_up);
+        if let (true, Some(rerender)) = (follow_up, &self.store.rerender) {
+            // Applets are still rendering at this point, so this only requests a render once the current one completes
+            let _ = rerender();
+        }
+    }
+
+    /// Creates a component in this scope
+    ///
+    /// # Arguments
+    ///
+    /// * `component`: The component, holding its properties
+    ///
+    /// returns: ComponentElement<C>, to which children may be added
+    pub fn component<C: Component>(&self, component: C) -> ComponentElement<C> {
+        let index = self.created.get();
+        self.created.set(index + 1);
+        ComponentElement {
+            scope: self.clone(),
+            id: index.to_string(),
+            component,
+            children: Children::default(),
+        }
+    }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Component with its children, as created through [`Scope::component`]
+pub struct ComponentElement<C: Component> {
+    scope: Scope,
+    /// Identifies the component within its scope
+    id: String,
+    component: C,
+    children: Children,
+}
+
+impl<C: Component> ComponentElement<C> {
+    /// Sets the key identifying this component within its scope, instead of its position
+    ///
+    /// Keyed components keep their state when other components are added, removed or reordered
+    pub fn key(mut self, key: impl Display) -> Self {
+        self.id = format!("key:{}", key);
+        self
+    }
+
+    /// Appends a child element, which may be another component or a form
+    pub fn child(mut self, child: impl DomElement + 'static) -> Self {
+        self.children.0.push(Box::new(child));
+        self
+    }
+
+    /// Appends child elements in order
+    pub fn children(mut self, children: impl IntoIterator<Item=Box<dyn DomElement>>) -> Self {
+        self.children.0.extend(children);
+        self
+    }
+}
+
+impl<C: Component> DomElement for ComponentElement<C> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let path = format!("{}/{}", self.scope.path, self.id);
+        let state = self.scope.store.state::<C>(&path);
+        let scope = Scope {
+            store: self.scope.store.clone(),
+            path: Rc::from(path),
+            created: Rc::new(Cell::new(0)),
+        };
+        self.component.render(&state, &self.children, &scope, renderer)
+    }
+}
+
+/// Handle to the state of a component
+///
+/// Cloning the handle is cheap, all clones refer to the same state.
+pub struct State<T> {
+    value: Rc<RefCell<T>>,
+    store: Rc<StateStore>,
+}
+
+impl<T> State<T> {
+    /// Current value of the state, which must be released before updating it
+    pub fn get(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Updates the state, re-rendering the applet
+    ///
+    /// If the applet is updating or rendering, such as when called from [`crate::Applet::update`] or from a listener that fires while nodes are patched,
+    /// the applet is re-rendered once that completes. Updates from [`Component::render`] only re-render once, see [`Scope::end_render`];
+    /// Prefer updating state from event handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `update`: Function modifying the state
+    ///
+    /// returns: Result<(), JsValue>
+    pub fn update(&self, update: impl FnOnce(&mut T)) -> Result<(), JsValue> {
+        update(&mut *self.value.borrow_mut());
+        if self.store.rendering.get() {
+            self.store.updated_while_rendering.set(true);
+            return Ok(());
+        }
+        match &self.store.rerender {
+            Some(rerender) => rerender(),
+            None => Ok(())
+        }
+    }
+
+    /// Replaces the state, re-rendering the applet, see [`State::update`]
+    pub fn set(&self, value: T) -> Result<(), JsValue> {
+        self.update(|state| *state = value)
+    }
+}
+
+impl<T> Clone for State<T> {
+    fn clone(&self) -> Self {
+        State { value: self.value.clone(), store: self.store.clone() }
+    }
+}
@@ -0,0 +1,60 @@
+//! Event listeners owning their closures
+//!
+//! Listeners added while rendering are owned by the renderer, and removed along with their element.
+//! [`EventListener`] can be used to listen for events on other targets, such as the window.
+
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::prelude::Closure;
+use web_sys::{Event, EventTarget};
+use crate::dom::Listener;
+
+/// Wraps an event handler into a [`Listener`]
+///
+/// The event is passed to the handler as `E` without checking its type, so `E` must match the type of the events listened for,
+/// such as [`web_sys::KeyboardEvent`] for `keydown` or [`web_sys::DragEvent`] for `drop`.
+/// Plain [`Event`] may be used for any event.
+pub fn listener<E: JsCast>(mut handler: impl FnMut(E) + 'static) -> Listener {
+    Box::new(move |event: Event| handler(event.unchecked_into()))
+}
+
+/// Event listener added to an event target, which is removed when dropped
+///
+/// The listener's closure is owned by this struct, and freed along with it.
+pub struct EventListener {
+    target: EventTarget,
+    event: String,
+    closure: Closure<dyn FnMut(Event)>,
+}
+
+impl EventListener {
+    /// Adds an event listener
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: Element or other event target to listen on
+    /// * `event`: Name of the event, e.g. "click"
+    /// * `handler`: Function called for each event, see [`listener`] for the event type
+    ///
+    /// returns: Result<EventListener, JsValue>
+    pub fn new<E: JsCast>(target: &EventTarget, event: &str, handler: impl FnMut(E) + 'static) -> Result<Self, JsValue> {
+        Self::from_listener(target, event, listener(handler))
+    }
+
+    /// Adds an already wrapped [`Listener`]
+    pub fn from_listener(target: &EventTarget, event: &str, listener: Listener) -> Result<Self, JsValue> {
+        let closure = Closure::wrap(listener);
+        target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())?;
+        Ok(EventListener { target: target.clone(), event: event.to_string(), closure })
+    }
+
+    /// Name of the event listened for
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+}
+
+impl Drop for EventListener {
+    fn drop(&mut self) {
+        let _ = self.target.remove_event_listener_with_callback(&self.event, self.closure.as_ref().unchecked_ref());
+    }
+}
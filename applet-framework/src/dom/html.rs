@@ -0,0 +1,311 @@
+//! In-memory renderer, serialising to HTML
+//!
+//! Does not require a browser, allowing elements to be rendered and inspected in native tests.
+
+use crate::dom::{Listener, MutableRenderer, NodeId, RenderError, Renderer};
+
+/// Elements that have no closing tag
+const VOID_ELEMENTS: [&str; 14] = ["area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"];
+
+/// Elements of which the text content is not escaped
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Node in an [`HtmlRenderer`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum HtmlNode {
+    Element {
+        tag: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<NodeId>,
+        /// Names of the events listened for, the listeners themselves are kept by the renderer
+        listeners: Vec<String>,
+    },
+    Text(String),
+    Fragment(Vec<NodeId>),
+}
+
+/// Renders into an in-memory node tree, which can be serialised to HTML
+///
+/// Event listeners are never called, but are kept until their node is released or the renderer is dropped, as in a browser
+#[derive(Default)]
+pub struct HtmlRenderer {
+    nodes: Vec<HtmlNode>,
+    /// Listeners with the node they were added to
+    listeners: Vec<(NodeId, Listener)>,
+}
+
+impl std::fmt::Debug for HtmlRenderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HtmlRenderer")
+            .field("nodes", &self.nodes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        HtmlRenderer { nodes: Vec::new(), listeners: Vec::new() }
+    }
+
+    /// Node for a handle created by this renderer
+    pub fn node(&self, node: NodeId) -> &HtmlNode {
+        &self.nodes[node.0]
+    }
+
+    /// Value of an attribute of an element, None if the node is not an element or does not have the attribute
+    pub fn attribute(&self, node: NodeId, name: &str) -> Option<&str> {
+        match self.node(node) {
+            HtmlNode::Element { attributes, .. } => attributes.iter()
+                .find(|(attribute, _)| attribute == name)
+                .map(|(_, value)| value.as_str()),
+            _ => None
+        }
+    }
+
+    /// Finds the first element matching a predicate, searching depth-first from `node`
+    pub fn find(&self, node: NodeId, predicate: &impl Fn(&HtmlNode) -> bool) -> Option<NodeId> {
+        if predicate(self.node(node)) {
+            return Some(node);
+        }
+        match self.node(node) {
+            HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => children.iter()
+                .find_map(|child| self.find(*child, predicate)),
+            HtmlNode::Text(_) => None
+        }
+    }
+
+    /// Finds all elements matching a predicate, in depth-first order from `node`
+    pub fn find_all(&self, node: NodeId, predicate: &impl Fn(&HtmlNode) -> bool) -> Vec<NodeId> {
+        let mut found = Vec::new();
+        self.collect(node, predicate, &mut found);
+        found
+    }
+
+    fn collect(&self, node: NodeId, predicate: &impl Fn(&HtmlNode) -> bool, found: &mut Vec<NodeId>) {
+        if predicate(self.node(node)) {
+            found.push(node);
+        }
+        match self.node(node) {
+            HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => {
+                for child in children {
+                    self.collect(*child, predicate, found);
+                }
+            }
+            HtmlNode::Text(_) => {}
+        }
+    }
+
+    /// Finds the first element with the specified attribute value, searching depth-first from `node`
+    pub fn find_by_attribute(&self, node: NodeId, name: &str, value: &str) -> Option<NodeId> {
+        self.find(node, &|html_node| match html_node {
+            HtmlNode::Element { attributes, .. } => attributes.iter().any(|(attribute, attribute_value)| attribute == name && attribute_value == value),
+            _ => false
+        })
+    }
+
+    /// Serialises a node and its children to HTML
+    pub fn to_html(&self, node: NodeId) -> String {
+        let mut html = String::new();
+        self.write_html(node, &mut html);
+        html
+    }
+
+    fn write_html(&self, node: NodeId, html: &mut String) {
+        match self.node(node) {
+            HtmlNode::Element { tag, attributes, children, .. } => {
+                html.push('<');
+                html.push_str(tag);
+                for (name, value) in attributes {
+                    html.push(' ');
+                    html.push_str(name);
+                    html.push_str("=\"");
+                    escape_into(value, html);
+                    html.push('"');
+                }
+                html.push('>');
+                if !VOID_ELEMENTS.contains(&&**tag) {
+                    for child in children {
+                        match self.node(*child) {
+                            HtmlNode::Text(text) if RAW_TEXT_ELEMENTS.contains(&&**tag) => html.push_str(text),
+                            _ => self.write_html(*child, html)
+                        }
+                    }
+                    html.push_str("</");
+                    html.push_str(tag);
+                    html.push('>');
+                }
+            }
+            HtmlNode::Text(text) => escape_into(text, html),
+            HtmlNode::Fragment(children) => {
+                for child in children {
+                    self.write_html(*child, html);
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, node: HtmlNode) -> NodeId {
+        self.nodes.push(node);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    fn node_mut(&mut self, node: NodeId) -> Result<&mut HtmlNode, RenderError> {
+        self.nodes.get_mut(node.0)
+            .ok_or_else(|| RenderError::new("node was not created by this renderer"))
+    }
+
+    /// Removes a node from the children of whichever node contains it
+    fn detach(&mut self, node: NodeId) {
+        for parent in &mut self.nodes {
+            if let HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) = parent {
+                children.retain(|child| *child != node);
+            }
+        }
+    }
+}
+
+/// Escapes text for use in HTML text or attribute values
+fn escape_into(text: &str, html: &mut String) {
+    for char in text.chars() {
+        match char {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(char)
+        }
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn create_element(&mut self, tag: &str) -> Result<NodeId, RenderError> {
+        Ok(self.push(HtmlNode::Element {
+            tag: tag.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            listeners: Vec::new(),
+        }))
+    }
+
+    fn create_text(&mut self, text: &str) -> Result<NodeId, RenderError> {
+        Ok(self.push(HtmlNode::Text(text.to_string())))
+    }
+
+    fn create_fragment(&mut self) -> Result<NodeId, RenderError> {
+        Ok(self.push(HtmlNode::Fragment(Vec::new())))
+    }
+
+    fn set_attribute(&mut self, node: NodeId, name: &str, value: &str) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            HtmlNode::Element { attributes, .. } => {
+                match attributes.iter_mut().find(|(attribute, _)| attribute == name) {
+                    Some((_, existing)) => *existing = value.to_string(),
+                    None => attributes.push((name.to_string(), value.to_string()))
+                }
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+
+    /// Appending a fragment moves its children, leaving the fragment empty as in the browser DOM
+    fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        let moved = match self.node_mut(child)? {
+            HtmlNode::Fragment(children) => std::mem::take(children),
+            _ => vec![child]
+        };
+        match self.node_mut(parent)? {
+            HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => {
+                children.extend(moved);
+                Ok(())
+            }
+            HtmlNode::Text(_) => Err(RenderError::new("text nodes cannot have children"))
+        }
+    }
+
+    fn add_listener(&mut self, node: NodeId, event: &str, listener: Listener) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            HtmlNode::Element { listeners, .. } => {
+                listeners.push(event.to_string());
+                self.listeners.push((node, listener));
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+}
+
+/// Released nodes are kept until the renderer is dropped, as handles are indices into the renderer's nodes. Their listeners are dropped when released
+impl MutableRenderer for HtmlRenderer {
+    fn insert_before(&mut self, parent: NodeId, child: NodeId, reference: Option<NodeId>) -> Result<(), RenderError> {
+        let moved = match self.node_mut(child)? {
+            HtmlNode::Fragment(children) => std::mem::take(children),
+            _ => vec![child]
+        };
+        for node in &moved {
+            self.detach(*node);
+        }
+        match self.node_mut(parent)? {
+            HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => {
+                let index = match reference {
+                    Some(reference) => children.iter()
+                        .position(|child| *child == reference)
+                        .ok_or_else(|| RenderError::new("reference node is not a child of the parent"))?,
+                    None => children.len()
+                };
+                children.splice(index..index, moved);
+                Ok(())
+            }
+            HtmlNode::Text(_) => Err(RenderError::new("text nodes cannot have children"))
+        }
+    }
+
+    fn remove_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        match self.node_mut(parent)? {
+            HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => match children.iter().position(|node| *node == child) {
+                Some(index) => {
+                    children.remove(index);
+                    Ok(())
+                }
+                None => Err(RenderError::new("node is not a child of the parent"))
+            },
+            HtmlNode::Text(_) => Err(RenderError::new("text nodes cannot have children"))
+        }
+    }
+
+    fn remove_attribute(&mut self, node: NodeId, name: &str) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            HtmlNode::Element { attributes, .. } => {
+                attributes.retain(|(attribute, _)| attribute != name);
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+
+    fn set_text(&mut self, node: NodeId, text: &str) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            HtmlNode::Text(existing) => {
+                *existing = text.to_string();
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not a text node"))
+        }
+    }
+
+    fn remove_listeners(&mut self, node: NodeId) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            HtmlNode::Element { listeners, .. } => {
+                listeners.clear();
+                self.listeners.retain(|(listener_node, _)| *listener_node != node);
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+
+    fn release(&mut self, node: NodeId) {
+        self.listeners.retain(|(listener_node, _)| *listener_node != node);
+    }
+}
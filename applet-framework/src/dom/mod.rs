@@ -1,36 +1,123 @@
-pub use web_sys::{Document, DocumentFragment, HtmlElement};
+pub use web_sys::{Document, DocumentFragment, DragEvent, Event, EventTarget, HtmlElement, InputEvent, KeyboardEvent, MouseEvent};
 pub use wasm_bindgen::{JsValue, JsCast};
 
-macro_rules! element {
-    ($document:expr, $tag:expr) => {
-        $crate::dom::JsCast::dyn_into::<$crate::dom::HtmlElement>($document.create_element($tag)?)
-            .expect("newly created element must be a HtmlElement")
-    };
-    ($document:expr, $tag:expr; $( $attribute:expr => $value:expr ),*) => {
-        {
-            let element = element!($document, $tag);
-            $(element.set_attribute($attribute, $value)?;)*
-            element
-        }
-    };
-    ($document:expr, $tag:expr; $( $attribute:expr => $value:expr ),*; Text = $text:expr) => {
-        {
-            let element = element!($document, $tag);
-            $(element.set_attribute($attribute, $value)?;)*
-            element.set_inner_text($text);
-            element
-        }
-    };
+pub mod builder;
+pub mod component;
+pub mod event;
+pub mod html;
+pub mod vdom;
+pub mod web;
+
+pub use builder::{Child, ElementBuilder, fragment};
+pub use component::{Children, Component, ComponentElement, Scope, State};
+pub use event::{EventListener, listener};
+
+/// Handle to a node created by a [`Renderer`]
+///
+/// Only valid for the renderer that created it
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct NodeId(pub(crate) usize);
+
+/// Event listener added through [`Renderer::add_listener`]
+///
+/// Listeners only receive events when rendered in a browser, the element they were added to is the event's `currentTarget`.
+/// Handlers for specific event types can be wrapped with [`listener`].
+pub type Listener = Box<dyn FnMut(Event)>;
+
+/// Error during rendering
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RenderError {
+    pub message: String
+}
+
+impl RenderError {
+    pub fn new(message: impl Into<String>) -> Self {
+        RenderError { message: message.into() }
+    }
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
-pub(crate) use {element};
+impl From<JsValue> for RenderError {
+    fn from(value: JsValue) -> Self {
+        RenderError { message: value.as_string().unwrap_or_else(|| format!("{:?}", value)) }
+    }
+}
+
+impl From<RenderError> for JsValue {
+    fn from(error: RenderError) -> Self {
+        JsValue::from_str(&error.message)
+    }
+}
+
+/// Target that DOM elements are rendered to
+///
+/// Implemented for the browser DOM by [`web::WebRenderer`], and for in-memory HTML by [`html::HtmlRenderer`]
+pub trait Renderer {
+    /// Creates a new element with the specified tag name
+    fn create_element(&mut self, tag: &str) -> Result<NodeId, RenderError>;
+    /// Creates a new text node, the text is not interpreted as HTML
+    fn create_text(&mut self, text: &str) -> Result<NodeId, RenderError>;
+    /// Creates a new document fragment, used to collect multiple nodes
+    fn create_fragment(&mut self) -> Result<NodeId, RenderError>;
+    /// Sets an attribute on an element
+    fn set_attribute(&mut self, node: NodeId, name: &str, value: &str) -> Result<(), RenderError>;
+    /// Appends a node to the children of another node, appending a fragment appends its children instead
+    fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError>;
+    /// Adds an event listener to an element
+    fn add_listener(&mut self, node: NodeId, event: &str, listener: Listener) -> Result<(), RenderError>;
+}
+
+/// Renderer that can also modify nodes after creating them, required to patch nodes through a [`vdom::VirtualDom`]
+pub trait MutableRenderer: Renderer {
+    /// Inserts a node before a child of `parent`, or appends it if `reference` is None
+    ///
+    /// A node that already has a parent is moved, as in the browser DOM
+    fn insert_before(&mut self, parent: NodeId, child: NodeId, reference: Option<NodeId>) -> Result<(), RenderError>;
+    /// Removes a node from the children of its parent
+    fn remove_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError>;
+    /// Removes an attribute from an element
+    fn remove_attribute(&mut self, node: NodeId, name: &str) -> Result<(), RenderError>;
+    /// Replaces the text of a text node
+    fn set_text(&mut self, node: NodeId, text: &str) -> Result<(), RenderError>;
+    /// Removes all event listeners added to an element through [`Renderer::add_listener`]
+    fn remove_listeners(&mut self, node: NodeId) -> Result<(), RenderError>;
+    /// Releases a node that is no longer used, the handle must not be used afterwards
+    fn release(&mut self, node: NodeId);
+}
 
 /// Trait for DOM/HTML element types
 pub trait DomElement {
     /// create the nodes for this type, collected into a document fragment
     ///
-    /// provides JsValue error types for error propagation
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue>;
+    /// returns: Result<NodeId, RenderError>, the created fragment
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError>;
+}
+
+impl DomElement for Vec<Box<dyn DomElement>> {
+    /// Creates the nodes of every element in order, collected into a single fragment
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        fragment(renderer, self.iter().map(|element| &**element))
+    }
+}
+
+/// &lt;p&gt; element containing plain text
+///
+/// Text is added as text node, and may safely contain user-input
+pub struct Paragraph {
+    pub text: String
+}
+
+impl DomElement for Paragraph {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        ElementBuilder::new("p")
+            .text(&*self.text)
+            .into_fragment(renderer)
+    }
 }
 
 /// Source for Applet Style
@@ -58,16 +145,14 @@ impl AppletStyle {
 }
 
 impl DomElement for AppletStyle {
-    /// Creates <style> element with content set through innerText
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
+    /// Creates &lt;style&gt; element with content set through a text node
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
         if let AppletStyle::NONE = self {
-            Ok(document.create_document_fragment())
+            renderer.create_fragment()
         } else {
-            let style = element!(document, "style");
-            style.set_inner_text(self.as_str());
-            let fragment = document.create_document_fragment();
-            fragment.append_child(&*style)?;
-            Ok(fragment)
+            ElementBuilder::new("style")
+                .text(self.as_str())
+                .into_fragment(renderer)
         }
     }
-}
\ No newline at end of file
+}
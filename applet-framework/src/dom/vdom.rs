@@ -0,0 +1,386 @@
+//! Virtual DOM, updating rendered nodes in place instead of replacing them
+//!
+//! [`DomElement`]s are rendered into a tree of [`VNode`]s by a [`VirtualRenderer`], which a [`VirtualDom`] compares against
+//! the previous render to only patch the nodes that changed. Nodes that are kept retain their state in the browser,
+//! such as focus, scroll position, selected files and typed text.
+//!
+//! Children are matched by position, or by key for elements that have a [`KEY_ATTRIBUTE`], see [`ElementBuilder::key`].
+//! Keyed elements keep their nodes when the list they are in is reordered, or has items inserted or removed.
+//!
+//! [`ElementBuilder::key`]: crate::dom::ElementBuilder::key
+
+use std::collections::HashMap;
+use crate::dom::{DomElement, Listener, MutableRenderer, NodeId, RenderError, Renderer};
+
+/// Attribute holding the key of an element, used to match elements across renders
+pub const KEY_ATTRIBUTE: &str = "data-key";
+
+/// Node in a virtual DOM tree
+pub enum VNode {
+    Element(VElement),
+    Text(String),
+}
+
+/// Element in a virtual DOM tree
+pub struct VElement {
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<VNode>,
+    /// Event listeners, which replace the listeners of the element when patched
+    pub listeners: Vec<(String, Listener)>,
+}
+
+impl VNode {
+    /// Renders a DOM element into virtual nodes
+    ///
+    /// # Arguments
+    ///
+    /// * `element`: Element to render
+    ///
+    /// returns: Result<Vec<VNode>, RenderError>, the top-level nodes of the element
+    pub fn from_element(element: &dyn DomElement) -> Result<Vec<VNode>, RenderError> {
+        let mut renderer = VirtualRenderer::new();
+        let root = element.to_nodes(&mut renderer)?;
+        Ok(renderer.take(root))
+    }
+
+    /// Key of this node, None for text nodes and elements without a key
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            VNode::Element(element) => attribute(&element.attributes, KEY_ATTRIBUTE),
+            VNode::Text(_) => None
+        }
+    }
+}
+
+/// Renders into a tree of [`VNode`]s, which are taken out of the renderer once rendering completes
+#[derive(Default)]
+pub struct VirtualRenderer {
+    nodes: Vec<RenderedNode>,
+}
+
+/// Node during rendering, with children referring to other nodes of the renderer
+enum RenderedNode {
+    Element {
+        tag: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<NodeId>,
+        listeners: Vec<(String, Listener)>,
+    },
+    Text(String),
+    Fragment(Vec<NodeId>),
+    /// Node that has been taken out of the renderer
+    Taken,
+}
+
+impl VirtualRenderer {
+    pub fn new() -> Self {
+        VirtualRenderer { nodes: Vec::new() }
+    }
+
+    /// Takes a node and its children out of the renderer
+    ///
+    /// returns: Vec<VNode>, the node itself, or the children of a fragment
+    pub fn take(&mut self, node: NodeId) -> Vec<VNode> {
+        match self.nodes.get_mut(node.0).map(|node| std::mem::replace(node, RenderedNode::Taken)) {
+            Some(RenderedNode::Element { tag, attributes, children, listeners }) => {
+                let children = children.into_iter()
+                    .flat_map(|child| self.take(child))
+                    .collect();
+                vec![VNode::Element(VElement { tag, attributes, children, listeners })]
+            }
+            Some(RenderedNode::Text(text)) => vec![VNode::Text(text)],
+            Some(RenderedNode::Fragment(children)) => children.into_iter()
+                .flat_map(|child| self.take(child))
+                .collect(),
+            Some(RenderedNode::Taken) | None => Vec::new()
+        }
+    }
+
+    fn push(&mut self, node: RenderedNode) -> NodeId {
+        self.nodes.push(node);
+        NodeId(self.nodes.len() - 1)
+    }
+
+    fn node_mut(&mut self, node: NodeId) -> Result<&mut RenderedNode, RenderError> {
+        self.nodes.get_mut(node.0)
+            .ok_or_else(|| RenderError::new("node was not created by this renderer"))
+    }
+}
+
+impl Renderer for VirtualRenderer {
+    fn create_element(&mut self, tag: &str) -> Result<NodeId, RenderError> {
+        Ok(self.push(RenderedNode::Element {
+            tag: tag.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            listeners: Vec::new(),
+        }))
+    }
+
+    fn create_text(&mut self, text: &str) -> Result<NodeId, RenderError> {
+        Ok(self.push(RenderedNode::Text(text.to_string())))
+    }
+
+    fn create_fragment(&mut self) -> Result<NodeId, RenderError> {
+        Ok(self.push(RenderedNode::Fragment(Vec::new())))
+    }
+
+    fn set_attribute(&mut self, node: NodeId, name: &str, value: &str) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            RenderedNode::Element { attributes, .. } => {
+                match attributes.iter_mut().find(|(attribute, _)| attribute == name) {
+                    Some((_, existing)) => *existing = value.to_string(),
+                    None => attributes.push((name.to_string(), value.to_string()))
+                }
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+
+    /// Appending a fragment moves its children, leaving the fragment empty as in the browser DOM
+    fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        let moved = match self.node_mut(child)? {
+            RenderedNode::Fragment(children) => std::mem::take(children),
+            _ => vec![child]
+        };
+        match self.node_mut(parent)? {
+            RenderedNode::Element { children, .. } | RenderedNode::Fragment(children) => {
+                children.extend(moved);
+                Ok(())
+            }
+            _ => Err(RenderError::new("node cannot have children"))
+        }
+    }
+
+    fn add_listener(&mut self, node: NodeId, event: &str, listener: Listener) -> Result<(), RenderError> {
+        match self.node_mut(node)? {
+            RenderedNode::Element { listeners, .. } => {
+                listeners.push((event.to_string(), listener));
+                Ok(())
+            }
+            _ => Err(RenderError::new("node is not an element"))
+        }
+    }
+}
+
+/// Nodes rendered into a root node, patched to match newly rendered [`VNode`]s
+///
+/// The root node should only contain nodes rendered through the virtual DOM, other nodes are left in place but may be reordered.
+pub struct VirtualDom {
+    root: NodeId,
+    children: Vec<MountedNode>,
+}
+
+/// Node created by a [`VirtualDom`], with the state it was last patched to
+struct MountedNode {
+    node: NodeId,
+    kind: MountedKind,
+}
+
+enum MountedKind {
+    Element {
+        tag: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<MountedNode>,
+    },
+    Text(String),
+}
+
+impl MountedNode {
+    fn key(&self) -> Option<&str> {
+        match &self.kind {
+            MountedKind::Element { attributes, .. } => attribute(attributes, KEY_ATTRIBUTE),
+            MountedKind::Text(_) => None
+        }
+    }
+
+    /// Whether this node can be patched into the specified node, rather than being replaced
+    fn matches(&self, vnode: &VNode) -> bool {
+        match (&self.kind, vnode) {
+            (MountedKind::Element { tag, .. }, VNode::Element(element)) => *tag == element.tag,
+            (MountedKind::Text(_), VNode::Text(_)) => true,
+            _ => false
+        }
+    }
+}
+
+impl VirtualDom {
+    /// Creates an empty virtual DOM, rendering into the specified root node
+    pub fn new(root: NodeId) -> Self {
+        VirtualDom { root, children: Vec::new() }
+    }
+
+    /// Node the virtual DOM renders into
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// Renders a DOM element, patching the nodes of the previous render
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer`: Renderer the root node was created with, the same renderer must be used for every render
+    /// * `element`: Element to render
+    ///
+    /// returns: Result<(), RenderError>
+    pub fn render(&mut self, renderer: &mut dyn MutableRenderer, element: &dyn DomElement) -> Result<(), RenderError> {
+        self.patch(renderer, VNode::from_element(element)?)
+    }
+
+    /// Patches the nodes of the previous render to match new virtual nodes
+    ///
+    /// Nodes of the previous render are kept if a node of the same type is rendered at the same position or with the same key,
+    /// other nodes are created or removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer`: Renderer the root node was created with, the same renderer must be used for every render
+    /// * `nodes`: Nodes to render into the root node
+    ///
+    /// returns: Result<(), RenderError>
+    pub fn patch(&mut self, renderer: &mut dyn MutableRenderer, nodes: Vec<VNode>) -> Result<(), RenderError> {
+        let previous = std::mem::take(&mut self.children);
+        self.children = patch_children(renderer, self.root, previous, nodes)?;
+        Ok(())
+    }
+
+    /// Removes all rendered nodes from the root node
+    pub fn clear(&mut self, renderer: &mut dyn MutableRenderer) -> Result<(), RenderError> {
+        for child in std::mem::take(&mut self.children) {
+            renderer.remove_child(self.root, child.node)?;
+            release(renderer, child);
+        }
+        Ok(())
+    }
+}
+
+/// Patches the children of a node, returning the new children in order
+fn patch_children(renderer: &mut dyn MutableRenderer, parent: NodeId, previous: Vec<MountedNode>, nodes: Vec<VNode>) -> Result<Vec<MountedNode>, RenderError> {
+    let previous_order: Vec<NodeId> = previous.iter().map(|child| child.node).collect();
+    let mut keyed = HashMap::new();
+    let mut unkeyed = Vec::new();
+    for (index, child) in previous.iter().enumerate() {
+        match child.key() {
+            Some(key) => {
+                keyed.entry(key.to_string()).or_insert(index);
+            }
+            None => unkeyed.push(index)
+        }
+    }
+    let mut unkeyed = unkeyed.into_iter();
+    let mut previous: Vec<Option<MountedNode>> = previous.into_iter().map(Some).collect();
+
+    let mut children = Vec::with_capacity(nodes.len());
+    for vnode in nodes {
+        let candidate = match vnode.key() {
+            Some(key) => keyed.remove(key),
+            None => unkeyed.next()
+        };
+        let reused = candidate.filter(|index| matches!(&previous[*index], Some(child) if child.matches(&vnode)))
+            .and_then(|index| previous[index].take());
+        children.push(match reused {
+            Some(child) => update(renderer, child, vnode)?,
+            None => create(renderer, vnode)?
+        });
+    }
+
+    let mut removed = Vec::new();
+    for child in previous.into_iter().flatten() {
+        renderer.remove_child(parent, child.node)?;
+        removed.push(child.node);
+        release(renderer, child);
+    }
+
+    // Move nodes into place, nodes before the current index are already in order
+    let mut order: Vec<NodeId> = previous_order.into_iter()
+        .filter(|node| !removed.contains(node))
+        .collect();
+    for (index, child) in children.iter().enumerate() {
+        if order.get(index) != Some(&child.node) {
+            renderer.insert_before(parent, child.node, order.get(index).copied())?;
+            order.retain(|node| *node != child.node);
+            order.insert(index, child.node);
+        }
+    }
+    Ok(children)
+}
+
+/// Patches a node to match a virtual node of the same type
+fn update(renderer: &mut dyn MutableRenderer, child: MountedNode, vnode: VNode) -> Result<MountedNode, RenderError> {
+    let node = child.node;
+    match (child.kind, vnode) {
+        (MountedKind::Text(previous), VNode::Text(text)) => {
+            if previous != text {
+                renderer.set_text(node, &text)?;
+            }
+            Ok(MountedNode { node, kind: MountedKind::Text(text) })
+        }
+        (MountedKind::Element { attributes: previous_attributes, children: previous_children, .. }, VNode::Element(VElement { tag, attributes, children, listeners })) => {
+            for (name, value) in &attributes {
+                if attribute(&previous_attributes, name) != Some(&**value) {
+                    renderer.set_attribute(node, name, value)?;
+                }
+            }
+            for (name, _) in &previous_attributes {
+                if attribute(&attributes, name).is_none() {
+                    renderer.remove_attribute(node, name)?;
+                }
+            }
+
+            // Listeners may capture state of the render they were created in, so are always replaced
+            renderer.remove_listeners(node)?;
+            for (event, listener) in listeners {
+                renderer.add_listener(node, &event, listener)?;
+            }
+
+            let children = patch_children(renderer, node, previous_children, children)?;
+            Ok(MountedNode { node, kind: MountedKind::Element { tag, attributes, children } })
+        }
+        _ => unreachable!("nodes are only updated with virtual nodes of the same type")
+    }
+}
+
+/// Creates the nodes for a virtual node, which are not yet appended to a parent
+fn create(renderer: &mut dyn MutableRenderer, vnode: VNode) -> Result<MountedNode, RenderError> {
+    match vnode {
+        VNode::Text(text) => {
+            let node = renderer.create_text(&text)?;
+            Ok(MountedNode { node, kind: MountedKind::Text(text) })
+        }
+        VNode::Element(VElement { tag, attributes, children, listeners }) => {
+            let node = renderer.create_element(&tag)?;
+            for (name, value) in &attributes {
+                renderer.set_attribute(node, name, value)?;
+            }
+            for (event, listener) in listeners {
+                renderer.add_listener(node, &event, listener)?;
+            }
+            let mut mounted_children = Vec::with_capacity(children.len());
+            for child in children {
+                let child = create(renderer, child)?;
+                renderer.append_child(node, child.node)?;
+                mounted_children.push(child);
+            }
+            Ok(MountedNode { node, kind: MountedKind::Element { tag, attributes, children: mounted_children } })
+        }
+    }
+}
+
+/// Releases a removed node and all of its children
+fn release(renderer: &mut dyn MutableRenderer, child: MountedNode) {
+    if let MountedKind::Element { children, .. } = child.kind {
+        for grandchild in children {
+            release(renderer, grandchild);
+        }
+    }
+    renderer.release(child.node);
+}
+
+/// Value of an attribute in a list of attributes
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes.iter()
+        .find(|(attribute, _)| attribute == name)
+        .map(|(_, value)| value.as_str())
+}
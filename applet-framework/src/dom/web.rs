@@ -0,0 +1,143 @@
+//! Renderer for the browser DOM
+
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, Node, Text};
+use crate::dom::{Listener, MutableRenderer, NodeId, RenderError, Renderer};
+use crate::dom::event::EventListener;
+
+/// Renders into DOM nodes created by a web_sys Document
+///
+/// The renderer owns the event listeners it adds, which are removed once their node is released or the renderer is dropped.
+/// Renderers used for content that stays in the document must therefore be kept, as [`crate::AppletHandle`] does.
+pub struct WebRenderer {
+    document: Document,
+    nodes: Vec<Option<WebNode>>,
+    /// Indices of released nodes, reused for new nodes
+    free: Vec<usize>,
+}
+
+/// DOM node created or adopted by a [`WebRenderer`]
+struct WebNode {
+    node: Node,
+    listeners: Vec<EventListener>,
+}
+
+impl WebRenderer {
+    pub fn new(document: Document) -> Self {
+        WebRenderer { document, nodes: Vec::new(), free: Vec::new() }
+    }
+
+    /// DOM node for a handle created by this renderer
+    pub fn node(&self, node: NodeId) -> &Node {
+        &self.web_node(node).node
+    }
+
+    /// Creates a handle for an existing DOM node, such as the shadowroot content is rendered into
+    pub fn adopt(&mut self, node: Node) -> NodeId {
+        self.push(node)
+    }
+
+    fn push(&mut self, node: Node) -> NodeId {
+        let node = Some(WebNode { node, listeners: Vec::new() });
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                NodeId(index)
+            }
+            None => {
+                self.nodes.push(node);
+                NodeId(self.nodes.len() - 1)
+            }
+        }
+    }
+
+    fn web_node(&self, node: NodeId) -> &WebNode {
+        self.nodes[node.0].as_ref().expect("node must not be used after being released")
+    }
+
+    fn web_node_mut(&mut self, node: NodeId) -> Result<&mut WebNode, RenderError> {
+        self.nodes.get_mut(node.0)
+            .and_then(Option::as_mut)
+            .ok_or_else(|| RenderError::new("node was released or not created by this renderer"))
+    }
+
+    fn element(&self, node: NodeId) -> Result<&Element, RenderError> {
+        self.node(node)
+            .dyn_ref::<Element>()
+            .ok_or_else(|| RenderError::new("node is not an element"))
+    }
+}
+
+impl Renderer for WebRenderer {
+    fn create_element(&mut self, tag: &str) -> Result<NodeId, RenderError> {
+        let element = self.document.create_element(tag)?;
+        Ok(self.push(element.into()))
+    }
+
+    fn create_text(&mut self, text: &str) -> Result<NodeId, RenderError> {
+        let text = self.document.create_text_node(text);
+        Ok(self.push(text.into()))
+    }
+
+    fn create_fragment(&mut self) -> Result<NodeId, RenderError> {
+        let fragment = self.document.create_document_fragment();
+        Ok(self.push(fragment.into()))
+    }
+
+    fn set_attribute(&mut self, node: NodeId, name: &str, value: &str) -> Result<(), RenderError> {
+        self.element(node)?.set_attribute(name, value)?;
+        Ok(())
+    }
+
+    fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        self.node(parent).append_child(self.node(child))?;
+        Ok(())
+    }
+
+    fn add_listener(&mut self, node: NodeId, event: &str, listener: Listener) -> Result<(), RenderError> {
+        let web_node = self.web_node_mut(node)?;
+        let listener = EventListener::from_listener(&web_node.node, event, listener)?;
+        web_node.listeners.push(listener);
+        Ok(())
+    }
+}
+
+impl MutableRenderer for WebRenderer {
+    fn insert_before(&mut self, parent: NodeId, child: NodeId, reference: Option<NodeId>) -> Result<(), RenderError> {
+        let reference = reference.map(|reference| self.node(reference));
+        self.node(parent).insert_before(self.node(child), reference)?;
+        Ok(())
+    }
+
+    fn remove_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        self.node(parent).remove_child(self.node(child))?;
+        Ok(())
+    }
+
+    fn remove_attribute(&mut self, node: NodeId, name: &str) -> Result<(), RenderError> {
+        self.element(node)?.remove_attribute(name)?;
+        Ok(())
+    }
+
+    fn set_text(&mut self, node: NodeId, text: &str) -> Result<(), RenderError> {
+        let node = self.node(node);
+        if node.is_instance_of::<Text>() {
+            node.set_text_content(Some(text));
+            Ok(())
+        } else {
+            Err(RenderError::new("node is not a text node"))
+        }
+    }
+
+    fn remove_listeners(&mut self, node: NodeId) -> Result<(), RenderError> {
+        self.web_node_mut(node)?.listeners.clear();
+        Ok(())
+    }
+
+    fn release(&mut self, node: NodeId) {
+        // Dropping the node's listeners removes them
+        if self.nodes.get_mut(node.0).and_then(Option::take).is_some() {
+            self.free.push(node.0);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Saving applet output as files
+
+use std::rc::Rc;
+use js_sys::{Array, Function, Uint8Array};
+use wasm_bindgen::prelude::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{console, Blob, BlobPropertyBag, Event, HtmlAnchorElement, Url};
+use crate::dom::{DomElement, ElementBuilder, NodeId, RenderError, Renderer};
+
+/// Time after which the object URL of a download is revoked
+///
+/// Browsers may only start reading the file some time after the click, such as once the user has chosen where to save it
+const REVOKE_DELAY_MS: i32 = 40_000;
+
+/// Offers a file to the user for download
+///
+/// The file is wrapped in a Blob with a temporary object URL, which is revoked some time after the download has started.
+///
+/// # Arguments
+///
+/// * `name`: Suggested file name
+/// * `mime_type`: MIME type of the file, e.g. "text/plain"
+/// * `bytes`: Contents of the file
+///
+/// returns: Result<(), JsValue>
+pub fn download(name: &str, mime_type: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("applet must run within browser window")?;
+    let document = window.document().ok_or("window must have document")?;
+    let body = document.body().ok_or("document must have body")?;
+
+    let url = Url::create_object_url_with_blob(&to_blob(mime_type, bytes)?)?;
+    let link = document.create_element("a")?
+        .unchecked_into::<HtmlAnchorElement>();
+    link.set_href(&url);
+    link.set_download(name);
+    // Some browsers ignore clicks on links that are not in the document
+    body.append_child(&link)?;
+    link.click();
+    link.remove();
+
+    // Revoking the URL while the download is starting may cancel it
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref::<Function>(), REVOKE_DELAY_MS)?;
+    Ok(())
+}
+
+/// Wraps bytes into a Blob with the specified MIME type
+fn to_blob(mime_type: &str, bytes: &[u8]) -> Result<Blob, JsValue> {
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_type);
+    Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+}
+
+/// &lt;a&gt; element which downloads a file when clicked, see [`download`]
+#[derive(Clone)]
+pub struct DownloadLink {
+    /// Text of the link
+    pub text: String,
+    /// Suggested file name
+    pub file_name: String,
+    /// MIME type of the file, e.g. "text/plain"
+    pub mime_type: String,
+    /// Contents of the file
+    pub bytes: Rc<[u8]>,
+}
+
+impl DomElement for DownloadLink {
+    /// Creates the link, the file's object URL is only created once the link is clicked
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let file = self.clone();
+        ElementBuilder::new("a")
+            .attribute("href", "#")
+            .class("download-link")
+            .text(&*self.text)
+            .on("click", move |event: Event| {
+                event.prevent_default();
+                if let Err(err) = download(&file.file_name, &file.mime_type, &file.bytes) {
+                    console::error_2(&"error during download:".into(), &err);
+                }
+            })
+            .into_fragment(renderer)
+    }
+}
@@ -0,0 +1,263 @@
+//! Simulated form submission, for testing forms outside of a browser
+//!
+//! The form is rendered with an [`HtmlRenderer`] to find its inputs and their default values,
+//! values can then be changed as a user would before the form is submitted.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use crate::data::FileHandle;
+use crate::dom::{NodeId, RenderError};
+use crate::dom::html::{HtmlNode, HtmlRenderer};
+use crate::form;
+use crate::form::{FormError, FormSource, FormValue, HTMLForm};
+
+/// Simulated user interaction with a form
+///
+/// Values are submitted the way a browser would, in document order with each checked or filled input contributing a value.
+/// Required inputs that are left empty prevent submission, as browsers do not submit such forms.
+pub struct FormHarness<F: HTMLForm> {
+    form: F,
+    values: Vec<(String, FormValue)>,
+    required: Vec<String>,
+}
+
+/// Error from a simulated form submission
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SubmitError {
+    /// The form did not validate, the callback was not called
+    Invalid(Vec<FormError>),
+    /// The callback returned an error
+    Callback(String),
+}
+
+impl Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Invalid(errors) => {
+                f.write_str("form is invalid")?;
+                for error in errors {
+                    write!(f, "; {}", error)?;
+                }
+                Ok(())
+            }
+            SubmitError::Callback(message) => f.write_str(message)
+        }
+    }
+}
+
+/// Sets whether the simulated browser supports an input type, for the current thread
+///
+/// Inputs such as [`Date`](crate::form::Date) render a text input instead of their own type if it is not supported.
+/// Outside of a browser, all input types are supported by default.
+///
+/// # Arguments
+///
+/// * `input_type`: Type of &lt;input&gt; element, e.g. "date"
+/// * `supported`: Whether the type is supported
+pub fn set_input_type_support(input_type: &'static str, supported: bool) {
+    form::set_input_type_support(input_type, supported);
+}
+
+impl<F: HTMLForm> FormHarness<F> {
+    /// Renders a form to read its inputs and their default values
+    ///
+    /// # Arguments
+    ///
+    /// * `form`: Form to submit
+    ///
+    /// returns: Result<FormHarness<F>, RenderError>
+    pub fn new(form: F) -> Result<Self, RenderError> {
+        let mut renderer = HtmlRenderer::new();
+        let root = form.to_nodes(&mut renderer)?;
+        let inputs = renderer.find_all(root, &|node| match node {
+            HtmlNode::Element { tag, .. } => tag == "input" || tag == "select" || tag == "textarea",
+            _ => false
+        });
+
+        let mut values = Vec::new();
+        let mut required = Vec::new();
+        for input in inputs {
+            let name = match renderer.attribute(input, "name") {
+                Some(name) => name.to_string(),
+                None => continue
+            };
+            if renderer.attribute(input, "required").is_some() && !required.contains(&name) {
+                required.push(name.clone());
+            }
+            if let HtmlNode::Element { tag, .. } = renderer.node(input) {
+                if tag == "select" {
+                    values.extend(selected_options(&renderer, input).into_iter().map(|value| (name.clone(), FormValue::Text(value))));
+                    continue;
+                } else if tag == "textarea" {
+                    values.push((name, FormValue::Text(text_content(&renderer, input))));
+                    continue;
+                }
+            }
+            match renderer.attribute(input, "type").unwrap_or("text") {
+                "submit" | "file" => {}
+                "checkbox" | "radio" => if renderer.attribute(input, "checked").is_some() {
+                    let value = renderer.attribute(input, "value").unwrap_or("on");
+                    values.push((name, FormValue::Text(value.to_string())));
+                }
+                _ => {
+                    let value = renderer.attribute(input, "value").unwrap_or("");
+                    values.push((name, FormValue::Text(value.to_string())));
+                }
+            }
+        }
+
+        Ok(FormHarness { form, values, required })
+    }
+
+    /// Replaces all values of an input with a single text value
+    pub fn set(&mut self, name: &str, value: impl Into<String>) -> &mut Self {
+        self.clear(name);
+        self.values.push((name.to_string(), FormValue::Text(value.into())));
+        self
+    }
+
+    /// Adds a text value to an input, used for inputs that submit multiple values
+    pub fn add(&mut self, name: &str, value: impl Into<String>) -> &mut Self {
+        self.values.push((name.to_string(), FormValue::Text(value.into())));
+        self
+    }
+
+    /// Selects a file in a file input, multiple files can be selected by calling this repeatedly
+    pub fn set_file(&mut self, name: &str, file: FileHandle) -> &mut Self {
+        self.values.push((name.to_string(), FormValue::File(file)));
+        self
+    }
+
+    /// Checks or unchecks a checkbox
+    pub fn check(&mut self, name: &str, checked: bool) -> &mut Self {
+        if checked {
+            self.set(name, "on")
+        } else {
+            self.clear(name)
+        }
+    }
+
+    /// Removes all values of an input, as if it were left empty
+    pub fn clear(&mut self, name: &str) -> &mut Self {
+        self.values.retain(|(value_name, _)| value_name != name);
+        self
+    }
+
+    /// Parses the current values through the form, without calling its callback
+    ///
+    /// returns: Result<F::Output, Vec<FormError>>, including an error for each required input left empty
+    pub fn parse(&self) -> Result<F::Output, Vec<FormError>> {
+        let missing: Vec<FormError> = self.required.iter()
+            .filter(|name| self.get_all(name).iter().all(is_blank))
+            .map(|name| FormError::new(name.clone(), "value is required"))
+            .collect();
+        if missing.is_empty() {
+            block_on(self.form.parse_form_data(self))
+        } else {
+            Err(missing)
+        }
+    }
+
+    /// Simulates a change to the form, calling its live mode callback with the parsed values without debouncing
+    ///
+    /// returns: bool, false if live mode is disabled
+    pub fn change(&self) -> bool {
+        match self.form.live_mode() {
+            Some(live_mode) => {
+                live_mode.call(self.parse());
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Submits the form, calling its callback with the parsed values
+    ///
+    /// returns: Result<(), SubmitError>
+    pub fn submit(&self) -> Result<(), SubmitError> {
+        let data = self.parse().map_err(SubmitError::Invalid)?;
+        block_on(self.form.submit_callback().call(data)).map_err(SubmitError::Callback)
+    }
+}
+
+impl<F: HTMLForm> FormSource for FormHarness<F> {
+    fn get(&self, name: &str) -> FormValue {
+        self.values.iter()
+            .find(|(value_name, _)| value_name == name)
+            .map_or(FormValue::Missing, |(_, value)| value.clone())
+    }
+
+    fn get_all(&self, name: &str) -> Vec<FormValue> {
+        self.values.iter()
+            .filter(|(value_name, _)| value_name == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+}
+
+/// Values of the selected options of a &lt;select&gt;
+///
+/// Browsers select the first option of single-value selects if none are explicitly selected
+fn selected_options(renderer: &HtmlRenderer, select: NodeId) -> Vec<String> {
+    let options = renderer.find_all(select, &|node| match node {
+        HtmlNode::Element { tag, .. } => tag == "option",
+        _ => false
+    });
+    let mut selected: Vec<NodeId> = options.iter()
+        .copied()
+        .filter(|option| renderer.attribute(*option, "selected").is_some())
+        .collect();
+    if selected.is_empty() && renderer.attribute(select, "multiple").is_none() {
+        selected.extend(options.first());
+    }
+    selected.into_iter()
+        .map(|option| renderer.attribute(option, "value").unwrap_or("").to_string())
+        .collect()
+}
+
+/// Concatenated text of a node's children
+fn text_content(renderer: &HtmlRenderer, node: NodeId) -> String {
+    match renderer.node(node) {
+        HtmlNode::Text(text) => text.clone(),
+        HtmlNode::Element { children, .. } | HtmlNode::Fragment(children) => children.iter()
+            .map(|child| text_content(renderer, *child))
+            .collect()
+    }
+}
+
+/// Whether a value would fail the browser's `required` check
+fn is_blank(value: &FormValue) -> bool {
+    match value {
+        FormValue::Text(text) => text.is_empty(),
+        FormValue::File(file) => file.is_unselected(),
+        FormValue::Missing | FormValue::List(_) => true
+    }
+}
+
+/// Wakes the thread blocked in [`block_on`]
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread
+///
+/// Futures that wait on browser APIs never complete outside of a browser, and block forever.
+fn block_on<T>(future: impl Future<Output=T>) -> T {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(value) => return value,
+            Poll::Pending => thread::park()
+        }
+    }
+}
@@ -1,8 +1,18 @@
-use chrono::{NaiveDate, NaiveDateTime};
-use crate::data::Color3;
-use crate::dom::{DomElement, element};
-use js_sys::{ArrayBuffer, Uint8Array};
-use wasm_bindgen_futures::JsFuture;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use chrono::{Datelike, FixedOffset, IsoWeek, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono::format::{Fixed, Item, Numeric, Pad, StrftimeItems};
+#[cfg(target_arch = "wasm32")]
+use chrono::Timelike;
+use crate::{Applet, AppletHandle};
+use crate::data;
+use crate::data::{ChunkedFile, Color3, EmailAddress, FileHandle, SelectedFile, YearMonth};
+use crate::dom::{DomElement, ElementBuilder, Event, Listener, NodeId, RenderError, Renderer};
+use js_sys::Array;
 
 pub use wasm_bindgen::{JsValue, JsCast};
 pub use wasm_bindgen::prelude::Closure;
@@ -14,7 +24,11 @@ pub use web_sys::{
     HtmlFormElement,
     FormData
 };
+use web_sys::{console, ClipboardEvent, DataTransfer, DragEvent, FileList, HtmlInputElement};
 pub use wasm_bindgen_futures::spawn_local;
+pub use applet_framework_derive::{ChoiceEnum, WebForm};
+
+pub mod harness;
 
 /// Form declaration macro
 ///
@@ -33,85 +47,96 @@ pub use wasm_bindgen_futures::spawn_local;
 /// }
 /// </pre>
 ///
+/// Input types may be generic, such as `Number<u8>` or `Select<Mode>`. Wrapped inputs are passed whole to the wrapper,
+/// e.g. `Optional<Text> { input: Text { form: "", name: "", ... } }`, and are given the form and name of the wrapper.
+///
 /// Concrete examples are available in the "applet-example" subproject
 #[macro_export]
 macro_rules! web_form {
     (
         $form_name:ident($form_id:ident) -> $result_name:ident {
             submit = $submit_value:expr,
-            $($input_id:ident = $input:tt {
+            $($input_id:ident = $input:ty {
                 $($field:ident: $value:expr),*
             }),+
         }
     ) => {
         /// Macro-generated form struct
+        #[derive(Clone)]
         struct $form_name {
             $($input_id: $input),+,
             submit: $crate::form::Submit,
-            on_submit_callback: fn($result_name)
+            on_submit_callback: $crate::form::SubmitCallback<$result_name>,
+            live_mode: Option<$crate::form::LiveMode<$result_name>>
         }
 
         impl $crate::dom::DomElement for $form_name {
-            fn to_nodes(&self, document: &$crate::form::Document) -> Result<$crate::form::DocumentFragment, $crate::form::JsValue> {
-                let form = $crate::form::JsCast::dyn_into::<$crate::form::HtmlElement>(document.create_element("form")?)
-                    .expect("newly created element must be a HtmlElement");
-                form.set_attribute("id", stringify!($form_id))?;
-                form.set_attribute("onsubmit", "return false;")?;   // Set onsubmit to cancel the form submission; So that our "proper" eventhandler does not have to handle this
-                $(form.append_child(&*$crate::dom::DomElement::to_nodes(&self.$input_id, document)?)?;)+
-                form.append_child(&*$crate::dom::DomElement::to_nodes(&self.submit, document)?)?;
-
-
-                let callback = self.on_submit_callback.clone();
-                let closure_box: Box<dyn Fn(&$crate::form::JsValue) -> ()> = Box::new(
-                    move |event| {
-                        let event = event.clone();
-                        $crate::form::spawn_local((async move |event: $crate::form::JsValue| {
-                            let result: Result<(), $crate::form::JsValue> = try {
-                                let target = $crate::form::Reflect::get(&event, &$crate::form::JsValue::from_str("target"))?;
-                                let form_data = $crate::form::FormData::new_with_form(
-                                    $crate::form::JsCast::unchecked_ref::<$crate::form::HtmlFormElement>(&target)
-                                )?;
-                                let data = $result_name {
-                                    $($input_id: <$input as $crate::form::FormInput>::parse(form_data.get(stringify!($input_id))).await?),+
-                                };
-                                callback(data)
-                            };
-                            match result {
-                                Ok(()) => (),
-                                Err(err) => panic!("error during form submission: {:?}", err)
-                            }
-                        })(event))
-                    });
-
-                let closure = $crate::form::Closure::wrap(closure_box)
-                    .into_js_value();
-
-                form.add_event_listener_with_callback(
-                    "submit",
-                    $crate::form::JsCast::unchecked_ref::<$crate::form::Function>(&closure)  // We can cast Closures to JS Functions
-                )?;
-
-                let fragment = document.create_document_fragment();
-                fragment.append_child(&*form)?;
-                Ok(fragment)
+            fn to_nodes(&self, renderer: &mut dyn $crate::dom::Renderer) -> Result<$crate::dom::NodeId, $crate::dom::RenderError> {
+                $crate::form::form_to_nodes(
+                    self,
+                    stringify!($form_id),
+                    &[$(&self.$input_id as &dyn $crate::dom::DomElement),+, &self.submit],
+                    &self.on_submit_callback,
+                    renderer
+                )
             }
         }
 
         impl $crate::form::HTMLForm for $form_name {
             type Output = $result_name;
 
-            fn new(on_submit: fn(Self::Output)) -> Self {
+            async fn parse_form_data(&self, form_data: &dyn $crate::form::FormSource) -> Result<Self::Output, Vec<$crate::form::FormError>> {
+                let mut errors = Vec::new();
+                $(
+                    let $input_id = match $crate::form::FormInput::parse(&self.$input_id, $crate::form::FormInput::form_value(&self.$input_id, form_data, stringify!($input_id))).await {
+                        Ok(value) => Some(value),
+                        Err(error) => {
+                            errors.push(error);
+                            None
+                        }
+                    };
+                )+
+                if errors.is_empty() {
+                    Ok($result_name {
+                        $($input_id: $input_id.expect("field without error must have been parsed")),+
+                    })
+                } else {
+                    Err(errors)
+                }
+            }
+
+            fn submit_callback(&self) -> &$crate::form::SubmitCallback<Self::Output> {
+                &self.on_submit_callback
+            }
+
+            fn live_mode(&self) -> Option<&$crate::form::LiveMode<Self::Output>> {
+                self.live_mode.as_ref()
+            }
+
+            fn with_live_mode(self, live_mode: $crate::form::LiveMode<Self::Output>) -> Self {
+                $form_name { live_mode: Some(live_mode), ..self }
+            }
+
+            fn with_callback(on_submit: $crate::form::SubmitCallback<Self::Output>) -> Self {
                 $form_name {
-                    submit: Submit {
+                    submit: $crate::form::Submit {
                         form: stringify!($form_id),
                         name: "submit",
                         value: $submit_value
                     },
                     on_submit_callback: on_submit,
-                    $($input_id: $input {
-                        form: stringify!($form_id),
-                        name: stringify!($input_id),
-                        $($field: $value),*
+                    live_mode: None,
+                    $($input_id: {
+                        // Struct expressions cannot name generic types directly, but can through an alias
+                        type Input = $input;
+                        let mut input = Input {
+                            form: stringify!($form_id),
+                            name: stringify!($input_id),
+                            $($field: $value),*
+                        };
+                        // Moves wrapped inputs to the form and name of their wrapper
+                        $crate::form::FormInput::set_name(&mut input, stringify!($form_id), stringify!($input_id));
+                        input
                     },)+
                 }
             }
@@ -125,10 +150,9 @@ macro_rules! web_form {
 }
 
 /// Trait for HTML Forms generated by the web_form! macro.
-pub trait HTMLForm: DomElement {
+pub trait HTMLForm: DomElement + Clone {
     /// Submitted data
-    type Output;
-
+    type Output: 'static;
 
     /// Constructs a new instance of this form
     ///
@@ -137,486 +161,2561 @@ pub trait HTMLForm: DomElement {
     /// * `on_submit`: Callback for form submission
     ///
     /// returns: Self
-    fn new(on_submit: fn(Self::Output)) -> Self;
-}
+    fn new(on_submit: impl FnMut(Self::Output) + 'static) -> Self {
+        Self::with_callback(SubmitCallback::new(on_submit))
+    }
 
-/// Trait for form &lt;input&gt; elements
-pub trait FormInput: DomElement {
-    /// Rust datatype for this input
+    /// Constructs a new instance of this form, with an asynchronous callback for form submission
     ///
-    /// This type should represent all input that validates the HTML-enforced input constraints
-    type Output;
+    /// The submit button is disabled until the returned future completes, errors are displayed next to the submit button.
+    ///
+    /// # Arguments
+    ///
+    /// * `on_submit`: Callback for form submission
+    ///
+    /// returns: Self
+    fn new_async<F, E>(on_submit: impl FnMut(Self::Output) -> F + 'static) -> Self
+        where F: Future<Output=Result<(), E>> + 'static,
+              E: Display
+    {
+        Self::with_callback(SubmitCallback::new_async(on_submit))
+    }
 
-    /// Parse the `value` field of the input to rust value
-    /// This function may still receive invalid or undefined values, and should not panic on doing so.
+    /// Constructs a new instance of this form, with a callback that receives a handle to the owning applet
+    ///
+    /// Generally used to send the submitted data to the applet as a message.
     ///
     /// # Arguments
     ///
-    /// * `value`: JS value provided by &lt;input&gt; element
+    /// * `handle`: Handle to the applet this form belongs to
+    /// * `on_submit`: Callback for form submission
     ///
-    /// returns: Result<Self::Output, JsValue>
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue>;
-}
+    /// returns: Self
+    fn with_applet<A: Applet>(handle: &AppletHandle<A>, mut on_submit: impl FnMut(&AppletHandle<A>, Self::Output) + 'static) -> Self {
+        let handle = handle.clone();
+        Self::new(move |output| on_submit(&handle, output))
+    }
 
-/// &lt;input type='checkbox'&gt;
-pub struct Checkbox {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub default: bool,
-}
+    /// Constructs a new instance of this form from an existing callback
+    ///
+    /// The callback may be shared between multiple forms
+    ///
+    /// # Arguments
+    ///
+    /// * `on_submit`: Callback for form submission
+    ///
+    /// returns: Self
+    fn with_callback(on_submit: SubmitCallback<Self::Output>) -> Self;
 
-impl DomElement for Checkbox {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let checkbox = element!(
-            document, "input";
-            "type" => "checkbox",
-            "name" => self.name,
-            "id" => &*id
-        );
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        if self.default {
-            checkbox.set_attribute("checked", "")?;
-        }
-
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*checkbox)?;
-        div.append_child(&*label)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
-    }
-}
+    /// Parses submitted form data through each input's [`FormInput::parse`]
+    ///
+    /// All fields are parsed, even if an earlier field fails to validate, so that every error can be reported at once
+    ///
+    /// # Arguments
+    ///
+    /// * `form_data`: Data of the submitted form
+    ///
+    /// returns: Result<Self::Output, Vec<FormError>>
+    async fn parse_form_data(&self, form_data: &dyn FormSource) -> Result<Self::Output, Vec<FormError>>;
 
-impl FormInput for Checkbox {
-    type Output = bool;
+    /// Callback for form submission, called with the parsed form data
+    fn submit_callback(&self) -> &SubmitCallback<Self::Output>;
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        Ok(value.eq(&JsValue::from_str("on")))
+    /// Enables live mode, in which the form is parsed whenever an input changes
+    ///
+    /// Changes are debounced, the callback is called once no input has changed for `debounce_ms` milliseconds.
+    /// The callback receives either the parsed form data, or the errors of all invalid fields. Submitting the form still calls the submit callback.
+    ///
+    /// # Arguments
+    ///
+    /// * `debounce_ms`: Time to wait after the last change before parsing the form, in milliseconds
+    /// * `on_change`: Callback for changes to the form
+    ///
+    /// returns: Self
+    fn on_change(self, debounce_ms: u32, on_change: impl FnMut(Result<Self::Output, Vec<FormError>>) + 'static) -> Self {
+        self.with_live_mode(LiveMode::new(debounce_ms, on_change))
     }
-}
 
-/// &lt;input type='color'&gt;
-pub struct Color {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub default: Option<Color3>,
+    /// Live mode of this form, None if it is disabled
+    fn live_mode(&self) -> Option<&LiveMode<Self::Output>>;
+
+    /// Enables live mode with an existing callback, see [`HTMLForm::on_change`]
+    fn with_live_mode(self, live_mode: LiveMode<Self::Output>) -> Self;
 }
 
-impl DomElement for Color {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let color_picker = element!(
-                document, "input";
-                "type" => "color",
-                "name" => self.name,
-                "id" => &*id
-            );
-        if let Some(default_color) = self.default {
-            color_picker.set_attribute("value", &*default_color.as_css_hex())?;
-        }
-
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*color_picker)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
-    }
+/// Validation error for a single form field
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FormError {
+    /// Name of the field that failed to validate
+    pub field: Cow<'static, str>,
+    /// Error message, displayed next to the field
+    pub message: String,
 }
 
-impl FormInput for Color {
-    type Output = Color3;
+impl FormError {
+    pub fn new(field: impl Into<Cow<'static, str>>, message: impl Into<String>) -> Self {
+        FormError { field: field.into(), message: message.into() }
+    }
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        value.as_string()
-            .as_ref()
-            .map(String::as_str)
-            .and_then(Color3::parse_from_hex)
-            .ok_or(JsValue::from_str("color input value was not valid color"))
+    /// Creates an error from a JS error value, using its string representation as message
+    pub fn from_js(field: impl Into<Cow<'static, str>>, value: &JsValue) -> Self {
+        FormError {
+            field: field.into(),
+            message: value.as_string().unwrap_or_else(|| format!("{:?}", value))
+        }
     }
 }
 
-/// &lt;input type='date'&gt;
-pub struct Date {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub default: Option<chrono::NaiveDate>,
-    pub min: Option<chrono::NaiveDate>,
-    pub max: Option<chrono::NaiveDate>,
+impl Display for FormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
 }
 
-impl DomElement for Date {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let date_picker = element!(
-            document, "input";
-            "type" => "date",
-            "name" => self.name,
-            "id" => &*id
-        );
+/// Future returned by submit callbacks, yielding an error message if the submission failed
+pub type SubmitFuture = Pin<Box<dyn Future<Output=Result<(), String>>>>;
 
-        if let Some(default_date) = self.default {
-            date_picker.set_attribute("value", &*default_date.format("%Y-%m-%d").to_string())?;
-        }
+/// Shared callback for form submission
+///
+/// Cloning the callback is cheap, all clones call the same function.
+pub struct SubmitCallback<T> {
+    function: Rc<RefCell<dyn FnMut(T) -> SubmitFuture>>
+}
 
-        if let Some(minimum_date) = self.min {
-            date_picker.set_attribute("min", &*minimum_date.format("%Y-%m-%d").to_string())?;
+impl<T> SubmitCallback<T> {
+    /// Wraps a closure into a submit callback
+    pub fn new(mut function: impl FnMut(T) + 'static) -> Self {
+        SubmitCallback {
+            function: Rc::new(RefCell::new(move |value| -> SubmitFuture {
+                function(value);
+                Box::pin(std::future::ready(Ok(())))
+            }))
         }
+    }
 
-        if let Some(maximum_date) = self.max {
-            date_picker.set_attribute("max", &*maximum_date.format("%Y-%m-%d").to_string())?;
+    /// Wraps an asynchronous closure into a submit callback
+    ///
+    /// Errors are converted into a message through their Display implementation
+    pub fn new_async<F, E>(mut function: impl FnMut(T) -> F + 'static) -> Self
+        where F: Future<Output=Result<(), E>> + 'static,
+              E: Display
+    {
+        SubmitCallback {
+            function: Rc::new(RefCell::new(move |value| -> SubmitFuture {
+                let future = function(value);
+                Box::pin(async move { future.await.map_err(|err| err.to_string()) })
+            }))
         }
+    }
 
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*date_picker)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+    /// Calls the wrapped closure, the returned future must be awaited for asynchronous callbacks to complete
+    ///
+    /// Panics if called recursively from within the callback itself
+    pub fn call(&self, value: T) -> SubmitFuture {
+        (self.function.borrow_mut())(value)
     }
 }
 
-impl FormInput for Date {
-    type Output = chrono::NaiveDate;
+impl<T> Clone for SubmitCallback<T> {
+    fn clone(&self) -> Self {
+        SubmitCallback { function: Rc::clone(&self.function) }
+    }
+}
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        NaiveDate::parse_from_str(
-            &*value.as_string().ok_or(JsValue::from_str("date input value was not valid date"))?,
-            "%Y-%m-%d",  // TODO: Deal with other possible date formats (And fallback to text input)
-        )
-            .map_err(|_| JsValue::from_str("date input value was not valid date"))
+impl<T: 'static> From<Rc<dyn Fn(T)>> for SubmitCallback<T> {
+    fn from(function: Rc<dyn Fn(T)>) -> Self {
+        SubmitCallback::new(move |value| function(value))
     }
 }
 
-/// &lt;input type='datetime-local'&gt;
-pub struct DateTime {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub default: Option<chrono::NaiveDateTime>,
-    pub min: Option<chrono::NaiveDateTime>,
-    pub max: Option<chrono::NaiveDateTime>,
+impl<T: 'static> From<Box<dyn FnMut(T)>> for SubmitCallback<T> {
+    fn from(function: Box<dyn FnMut(T)>) -> Self {
+        SubmitCallback::new(function)
+    }
 }
 
-impl DomElement for DateTime {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let date_picker = element!(
-            document, "input";
-            "type" => "datetime-local",
-            "name" => self.name,
-            "id" => &*id
-        );
+/// Callback for changes to a form in live mode, receiving the parsed form data or the errors of all invalid fields
+pub type ChangeFunction<T> = dyn FnMut(Result<T, Vec<FormError>>);
 
-        if let Some(default_date) = self.default {
-            date_picker.set_attribute("value", &*default_date.format("%Y-%m-%dT%H:%M").to_string())?;
-        }
+/// Live mode of a form, see [`HTMLForm::on_change`]
+///
+/// Cloning the live mode is cheap, all clones call the same function.
+pub struct LiveMode<T> {
+    debounce_ms: u32,
+    function: Rc<RefCell<ChangeFunction<T>>>,
+}
 
-        if let Some(minimum_date) = self.min {
-            date_picker.set_attribute("min", &*minimum_date.format("%Y-%m-%dT%H:%M").to_string())?;
-        }
+impl<T> LiveMode<T> {
+    /// Wraps a closure into a live mode callback
+    ///
+    /// # Arguments
+    ///
+    /// * `debounce_ms`: Time to wait after the last change before parsing the form, in milliseconds
+    /// * `function`: Callback for changes to the form
+    ///
+    /// returns: LiveMode<T>
+    pub fn new(debounce_ms: u32, function: impl FnMut(Result<T, Vec<FormError>>) + 'static) -> Self {
+        LiveMode { debounce_ms, function: Rc::new(RefCell::new(function)) }
+    }
 
-        if let Some(maximum_date) = self.max {
-            date_picker.set_attribute("max", &*maximum_date.format("%Y-%m-%dT%H:%M").to_string())?;
-        }
+    pub fn debounce_ms(&self) -> u32 {
+        self.debounce_ms
+    }
 
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*date_picker)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+    /// Calls the wrapped closure
+    ///
+    /// Panics if called recursively from within the callback itself
+    pub fn call(&self, value: Result<T, Vec<FormError>>) {
+        (self.function.borrow_mut())(value)
     }
 }
 
-impl FormInput for DateTime {
-    type Output = chrono::NaiveDateTime;
+impl<T> Clone for LiveMode<T> {
+    fn clone(&self) -> Self {
+        LiveMode { debounce_ms: self.debounce_ms, function: Rc::clone(&self.function) }
+    }
+}
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        NaiveDateTime::parse_from_str(
-            &*value.as_string().ok_or(JsValue::from_str("datetime input value was not valid datetime"))?,
-            "%Y-%m-%dT%H:%M",  // TODO: Deal with other possible date formats (And fallback to text input)
-        )
-            .map_err(|_| JsValue::from_str("datetime input value was not valid datetime"))
+/// Creates the &lt;form&gt; element for a form struct, generally used indirectly through the web_form! macro
+///
+/// Submitting the form parses the form data and calls the callback, see [`run_submission`].
+/// If live mode is enabled, changes to the form's inputs also parse the form data, see [`HTMLForm::on_change`]
+///
+/// # Arguments
+///
+/// * `definition`: Form struct to create the &lt;form&gt; element for
+/// * `form_id`: ID of the &lt;form&gt; element
+/// * `inputs`: Inputs of the form, including its submit button
+/// * `callback`: Callback for form submission
+/// * `renderer`: Renderer to create the element with
+///
+/// returns: Result<NodeId, RenderError>
+pub fn form_to_nodes<F: HTMLForm + 'static>(definition: &F, form_id: &str, inputs: &[&dyn DomElement], callback: &SubmitCallback<F::Output>, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+    let definition = Rc::new(definition.clone());
+    let mut form = ElementBuilder::new("form")
+        .attribute("id", form_id)
+        .attribute("onsubmit", "return false;")   // Set onsubmit to cancel the form submission; So that our "proper" eventhandler does not have to handle this
+        .children(inputs.iter().copied());
+    if let Some(live_mode) = definition.live_mode() {
+        form = with_change_listeners(form, definition.clone(), live_mode.clone());
     }
+
+    let callback = callback.clone();
+    form.on("submit", move |event: Event| {
+        let form = current_target(&event);
+        let definition = definition.clone();
+        let callback = callback.clone();
+        spawn_local(async move {
+            if let Err(err) = run_submission(&form, &*definition, &callback).await {
+                show_status_error(&form, "error during form submission", &err);
+            }
+        })
+    })
+        .into_fragment(renderer)
 }
 
+/// Adds the listeners for a form's live mode, which parse the form once its inputs stop changing
+fn with_change_listeners<'a, F: HTMLForm + 'static>(mut form: ElementBuilder<'a>, definition: Rc<F>, live_mode: LiveMode<F::Output>) -> ElementBuilder<'a> {
+    // Text inputs fire input events, while others may only fire change events
+    for event_name in ["input", "change"] {
+        let definition = definition.clone();
+        let live_mode = live_mode.clone();
+        form = form.on(event_name, move |event: Event| {
+            let form = current_target(&event);
+            let pending_form = form.clone();
+            let definition = definition.clone();
+            let callback = live_mode.clone();
+            let change = PendingChange::new(live_mode.debounce_ms(), move || {
+                // The change owns this callback, so it is only dropped once the callback has returned
+                let completed = PendingChange::take(&form);
+                spawn_local(async move {
+                    drop(completed);
+                    match read_form_data(&form) {
+                        Ok(form_data) => callback.call(definition.parse_form_data(&form_data).await),
+                        Err(err) => show_status_error(&form, "error during form change", &err)
+                    }
+                })
+            });
+            match change {
+                Ok(change) => PendingChange::replace(pending_form, change),
+                Err(err) => show_status_error(&pending_form, "error during form change", &err)
+            }
+        });
+    }
+    form
+}
 
-// pub struct Email;
+thread_local! {
+    /// Pending changes by form, kept apart from the form's listeners as those are replaced whenever the applet re-renders
+    static PENDING_CHANGES: RefCell<Vec<(HtmlElement, PendingChange)>> = const { RefCell::new(Vec::new()) };
+}
 
-/// &lt;input type='file'&gt;
-///
-/// Currently handles no-selected-file by yielding a 0-sized slice
-pub struct File {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub accept: &'static str,
-    pub multiple: bool,
+/// Debounced form change, which is cancelled when dropped
+struct PendingChange {
+    timeout: i32,
+    _callback: Closure<dyn FnMut()>,
 }
 
-impl DomElement for File {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let file_select = element!(
-            document, "input";
-            "type" => "file",
-            "id" => &*id,
-            "name" => self.name,
-            "accept" => self.accept
-        );
-        if self.multiple {
-            file_select.set_attribute("multiple", "")?;
-        }
-
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*file_select)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+impl PendingChange {
+    fn new(delay_ms: u32, callback: impl FnOnce() + 'static) -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or("forms must be used in a window")?;
+        let callback = Closure::once(callback);
+        let timeout = window.set_timeout_with_callback_and_timeout_and_arguments_0(callback.as_ref().unchecked_ref(), delay_ms as i32)?;
+        Ok(PendingChange { timeout, _callback: callback })
+    }
+
+    /// Sets the pending change of a form, cancelling its previous change
+    fn replace(form: HtmlElement, change: PendingChange) {
+        let previous = Self::take(&form);
+        PENDING_CHANGES.with(|changes| changes.borrow_mut().push((form, change)));
+        drop(previous);
+    }
+
+    /// Removes the pending change of a form, which is cancelled once dropped
+    fn take(form: &HtmlElement) -> Option<PendingChange> {
+        PENDING_CHANGES.with(|changes| {
+            let mut changes = changes.borrow_mut();
+            let index = changes.iter().position(|(pending_form, _)| pending_form == form)?;
+            Some(changes.remove(index).1)
+        })
     }
 }
 
-impl FormInput for File {
-    type Output = Box<[u8]>;
+impl Drop for PendingChange {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            window.clear_timeout_with_handle(self.timeout);
+        }
+    }
+}
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        let file = value.dyn_into::<web_sys::File>()
-            .map_err(|_| JsValue::from_str("file input value was not valid file"))?;
-        let buff: ArrayBuffer = JsFuture::from(file.array_buffer())
-            .await?
-            .dyn_into()
-            .expect("array_buffer() must return array buffer");
+thread_local! {
+    /// Files dropped onto drop zones in browsers that cannot assign them to the zone's file input
+    static DROPPED_FILES: RefCell<Vec<(HtmlInputElement, web_sys::File)>> = const { RefCell::new(Vec::new()) };
+}
 
-        let u8_array = Uint8Array::new(&*buff);
+/// File dropped onto a [`DropZone`], submitted in place of the value of its file input
+///
+/// Browsers without the DataTransfer constructor cannot assign dropped files to a file input, so they are kept until the form is read.
+struct DroppedFile;
+
+impl DroppedFile {
+    /// Sets the file dropped onto an input, None when the user selects a file through the input itself
+    fn replace(input: &HtmlInputElement, file: Option<web_sys::File>) {
+        DROPPED_FILES.with(|dropped| {
+            let mut dropped = dropped.borrow_mut();
+            // Inputs that were removed from the document can no longer be submitted
+            dropped.retain(|(dropped_input, _)| dropped_input != input && dropped_input.is_connected());
+            if let Some(file) = file {
+                dropped.push((input.clone(), file));
+            }
+        });
+    }
 
-        Ok(u8_array.to_vec().into_boxed_slice())
+    /// Sets the files dropped onto the inputs of a form in its form data
+    fn add_to(form: &HtmlElement, form_data: &FormData) -> Result<(), JsValue> {
+        DROPPED_FILES.with(|dropped| {
+            for (input, file) in dropped.borrow().iter().filter(|(input, _)| form.contains(Some(input))) {
+                form_data.set_with_blob(&input.name(), file)?;
+            }
+            Ok(())
+        })
     }
 }
 
-// pub struct Hidden;
-// pub struct Image;   // Use a regular submit
-// pub struct Month;
+/// Reads the current values of a form, including files dropped onto its drop zones
+fn read_form_data(form: &HtmlElement) -> Result<FormData, JsValue> {
+    let form_data = FormData::new_with_form(form.unchecked_ref::<HtmlFormElement>())?;
+    DroppedFile::add_to(form, &form_data)?;
+    Ok(form_data)
+}
 
-/// &lt;input type='number'&gt;
-pub struct Number {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: String,
-    pub default: Option<f64>,
-    pub min: Option<f64>,
-    pub max: Option<f64>,
+/// Element an event listener was added to
+fn current_target(event: &Event) -> HtmlElement {
+    event.current_target()
+        .expect("event must have current target while dispatched")
+        .unchecked_into()
 }
 
-impl DomElement for Number {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let number = element!(
-            document, "input";
-            "type" => "number",
-            "name" => self.name,
-            "id" => &*id
-        );
+/// Runs a form submission, generally used indirectly through the web_form! macro
+///
+/// The callback is only called if all fields validate, otherwise the errors are displayed next to their fields.
+/// The form's submit button is disabled while the callback runs, and any error it returns is displayed in the form's status element.
+///
+/// # Arguments
+///
+/// * `form`: The submitted &lt;form&gt; element
+/// * `definition`: Form struct the &lt;form&gt; element was created from
+/// * `callback`: Callback to pass the parsed data to
+///
+/// returns: Result<(), JsValue>
+pub async fn run_submission<F: HTMLForm>(form: &HtmlElement, definition: &F, callback: &SubmitCallback<F::Output>) -> Result<(), JsValue> {
+    let submit_button = form.query_selector("[type='submit']")?;
+    let status = form.query_selector(".form-status")?;
 
-        if let Some(default) = self.default {
-            number.set_attribute("value", &*format!("{}", default))?;
-        }
+    if let Some(status) = &status {
+        status.set_text_content(None);
+    }
+    while let Some(error) = form.query_selector(".form-error")? {
+        error.remove();
+    }
 
-        if let Some(min) = self.min {
-            number.set_attribute("min", &*format!("{}", min))?;
-        }
+    let form_data = read_form_data(form)?;
+    if let Some(submit_button) = &submit_button {
+        submit_button.set_attribute("disabled", "")?;
+    }
 
-        if let Some(max) = self.max {
-            number.set_attribute("max", &*format!("{}", max))?;
+    let result = match definition.parse_form_data(&form_data).await {
+        Ok(data) => callback.call(data).await,
+        Err(errors) => {
+            for error in &errors {
+                show_form_error(form, error)?;
+            }
+            Ok(())
         }
+    };
 
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*number)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+    if let Some(submit_button) = &submit_button {
+        submit_button.remove_attribute("disabled")?;
     }
+    if let (Err(message), Some(status)) = (result, &status) {
+        status.set_text_content(Some(&*message));
+    }
+    Ok(())
 }
 
-impl FormInput for Number {
-    type Output = f64;
-
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        f64::try_from(value)
-            .map_err(|_| JsValue::from_str("number input value was not number"))
+/// Displays an error that prevented a form from being submitted or read in the form's status element, re-enabling its submit button
+///
+/// Forms without a status element, such as live forms without a [`Submit`] input, log the error to the console instead.
+fn show_status_error(form: &HtmlElement, context: &str, err: &JsValue) {
+    let message = err.dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .or_else(|| err.as_string())
+        .unwrap_or_else(|| format!("{:?}", err));
+    match form.query_selector(".form-status") {
+        Ok(Some(status)) => status.set_text_content(Some(&format!("{}: {}", context, message))),
+        _ => console::error_2(&context.into(), err)
+    }
+    if let Ok(Some(submit_button)) = form.query_selector("[type='submit']") {
+        let _ = submit_button.remove_attribute("disabled");
     }
 }
 
-// pub struct Password;
-
-/// &lt;input type='radio'&gt;
+/// Displays a validation error after the input it belongs to
 ///
-/// Caution: The data type for this input may return any string, and should not be displayed.
-pub struct Radio<const N: usize> {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub label: [String; N],
-    pub value: [&'static str; N]    // TODO: Replace value with enum type to remove XSS risk
+/// Errors for fields without a matching input are appended to the end of the form.
+fn show_form_error(form: &HtmlElement, error: &FormError) -> Result<(), JsValue> {
+    let group = match form.query_selector(&format!("[name='{}']", error.field))? {
+        Some(input) => input.closest(".form-group")?,
+        None => None
+    };
+    let document = form.owner_document().ok_or("form must belong to document")?;
+    let message = document.create_element("span")?;
+    message.set_attribute("class", "form-error")?;
+    message.set_text_content(Some(&*error.message));
+    match group {
+        Some(group) => group.append_child(&message)?,
+        None => form.append_child(&message)?
+    };
+    Ok(())
 }
 
-impl<const N: usize> DomElement for Radio<N> {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let fragment = document.create_document_fragment();
+/// Value submitted for a form input
+#[derive(Debug, Clone)]
+pub enum FormValue {
+    /// No value was submitted, e.g. for unchecked checkboxes
+    Missing,
+    /// String value
+    Text(String),
+    /// File selected in a file input
+    File(FileHandle),
+    /// All values submitted with the same name, see [`FormInput::form_value`]
+    List(Vec<FormValue>),
+}
 
-        for (label, value) in self.label.iter().zip(&self.value) {
-            let id = format!("{}-{}", self.form, self.name);
-                let label = element!(
-                document, "label";
-                "for" => &*id;
-                Text = &*label
-            );
-            let radio = element!(
-                document, "input";
-                "type" => "file",
-                "id" => &*id,
-                "name" => self.name,
-                "value" => value
-            );
+impl FormValue {
+    /// Converts a value from browser FormData
+    pub fn from_js(value: JsValue) -> Self {
+        if let Some(text) = value.as_string() {
+            FormValue::Text(text)
+        } else if let Some(file) = value.dyn_ref::<web_sys::File>() {
+            FormValue::File(FileHandle::from(file.clone()))
+        } else if let Some(values) = value.dyn_ref::<Array>() {
+            FormValue::List(values.iter().map(FormValue::from_js).collect())
+        } else {
+            FormValue::Missing
+        }
+    }
 
-            let div = document.create_element("div")?;
-            div.set_attribute("class", "form-group")?;
-            div.append_child(&*label)?;
-            div.append_child(&*radio)?;
-            fragment.append_child(&*div)?;
+    /// String value, None if the value is not a string
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            FormValue::Text(text) => Some(&**text),
+            _ => None
         }
-        Ok(fragment)
     }
 }
 
-impl<const N: usize>  FormInput for Radio<N> {
-    type Output = String;
+/// Source of submitted form values
+///
+/// Implemented for browser FormData, and by [`harness::FormHarness`] to simulate form submission
+pub trait FormSource {
+    /// First value submitted with a name, [`FormValue::Missing`] if there is none
+    fn get(&self, name: &str) -> FormValue;
+    /// All values submitted with a name
+    fn get_all(&self, name: &str) -> Vec<FormValue>;
+}
+
+impl FormSource for FormData {
+    fn get(&self, name: &str) -> FormValue {
+        FormValue::from_js(FormData::get(self, name))
+    }
 
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        value.as_string()
-            .ok_or(JsValue::from_str("radio input value was not valid string"))
+    fn get_all(&self, name: &str) -> Vec<FormValue> {
+        FormData::get_all(self, name)
+            .iter()
+            .map(FormValue::from_js)
+            .collect()
     }
 }
 
-// pub struct Range;
-// pub struct Search;
+/// Trait for form &lt;input&gt; elements
+pub trait FormInput: DomElement + Clone {
+    /// Rust datatype for this input
+    ///
+    /// This type should represent all input that validates the HTML-enforced input constraints
+    type Output;
 
-/// &lt;input type='submit'&gt;
-///
-/// Special case in the web_form! macro, and does not need to be added
-pub struct Submit {
-    pub form: &'static str,
-    pub name: &'static str,
-    pub value: String
-}
+    /// Parse the `value` field of the input to rust value
+    /// This function may still receive invalid or undefined values, and should not panic on doing so.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: Value submitted for the &lt;input&gt; element
+    ///
+    /// returns: Result<Self::Output, FormError>
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError>;
 
-impl DomElement for Submit {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let submit = element!(
-            document, "input";
-            "type" => "submit",
-            "id" => &*format!("{}-{}", self.form, self.name),
-            "value" => &*self.value
-        );
+    /// Reads the value passed to [`FormInput::parse`] from submitted form data
+    ///
+    /// By default, reads the first value with the input's name
+    ///
+    /// # Arguments
+    ///
+    /// * `form_data`: Data of the submitted form
+    /// * `name`: Name of this input
+    ///
+    /// returns: FormValue
+    fn form_value(&self, form_data: &dyn FormSource, name: &str) -> FormValue {
+        form_data.get(name)
+    }
 
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*submit)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+    /// Whether the `value` field of the input is empty, used by [`Optional`] to yield None
+    ///
+    /// By default, missing values and empty strings are considered empty
+    fn is_empty(&self, value: &FormValue) -> bool {
+        match value {
+            FormValue::Missing => true,
+            FormValue::Text(text) => text.is_empty(),
+            FormValue::File(file) => file.is_unselected(),
+            FormValue::List(values) => values.iter().all(|value| self.is_empty(value))
+        }
     }
+
+    /// Moves this input to a form and name, used by [`Optional`] to give the wrapped input its own form and name
+    ///
+    /// # Arguments
+    ///
+    /// * `form`: ID of the form the input belongs to
+    /// * `name`: Name of the input, under which its value is submitted
+    fn set_name(&mut self, form: &'static str, name: &'static str);
 }
 
-// pub struct Telephone;
+/// Wraps an input into a form group, preceded by its label
+fn labelled<'a>(id: &str, label: &str, input: ElementBuilder<'a>) -> ElementBuilder<'a> {
+    ElementBuilder::new("div")
+        .class("form-group")
+        .child(ElementBuilder::new("label")
+            .attribute("for", id)
+            .text(label))
+        .child(input)
+}
 
-/// &lt;input type='text'&gt;
+/// Wrapper making an input optional, yielding None if the input is left empty
 ///
-/// Warning: Provides direct user-input String. Subject to XSS risks
-pub struct Text {
+/// Omits the `required` attribute that inputs without a valid empty value otherwise set.
+/// The wrapped input must have the same `form` and `name` as the wrapper, which [`Optional::new`] ensures.
+#[derive(Clone)]
+pub struct Optional<I: FormInput> {
     pub form: &'static str,
     pub name: &'static str,
-    pub label: String,
-    pub value: String
+    pub input: I,
 }
 
-impl DomElement for Text {
-    fn to_nodes(&self, document: &Document) -> Result<DocumentFragment, JsValue> {
-        let id = format!("{}-{}", self.form, self.name);
-        let label = element!(
-            document, "label";
-            "for" => &*id;
-            Text = &*self.label
-        );
-        let text = element!(
-            document, "input";
-            "type" => "text",
-            "id" => &*id,
-            "name" => self.name,
-            "value" => &*self.value
-        );
-
-        let div = document.create_element("div")?;
-        div.set_attribute("class", "form-group")?;
-        div.append_child(&*label)?;
-        div.append_child(&*text)?;
-        let fragment = document.create_document_fragment();
-        fragment.append_child(&*div)?;
-        Ok(fragment)
+impl<I: FormInput> Optional<I> {
+    /// Wraps an input, replacing its form and name with those of the wrapper
+    ///
+    /// # Arguments
+    ///
+    /// * `form`: ID of the form the input belongs to
+    /// * `name`: Name of the input
+    /// * `input`: The wrapped input
+    ///
+    /// returns: Optional<I>
+    pub fn new(form: &'static str, name: &'static str, mut input: I) -> Self {
+        input.set_name(form, name);
+        Optional { form, name, input }
     }
 }
 
-impl FormInput for Text {
-    type Output = String;
-
-    async fn parse(value: JsValue) -> Result<Self::Output, JsValue> {
-        value.as_string().ok_or(JsValue::from_str("text input value was not string"))
+impl<I: FormInput> DomElement for Optional<I> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        self.input.to_nodes(&mut WithoutRequired(renderer))
     }
 }
 
-// pub struct ClockTime;
+/// Renderer adapter that discards `required` attributes
+struct WithoutRequired<'a>(&'a mut dyn Renderer);
+
+impl Renderer for WithoutRequired<'_> {
+    fn create_element(&mut self, tag: &str) -> Result<NodeId, RenderError> {
+        self.0.create_element(tag)
+    }
+
+    fn create_text(&mut self, text: &str) -> Result<NodeId, RenderError> {
+        self.0.create_text(text)
+    }
+
+    fn create_fragment(&mut self) -> Result<NodeId, RenderError> {
+        self.0.create_fragment()
+    }
+
+    fn set_attribute(&mut self, node: NodeId, name: &str, value: &str) -> Result<(), RenderError> {
+        if name == "required" {
+            Ok(())
+        } else {
+            self.0.set_attribute(node, name, value)
+        }
+    }
 
-// pub struct URL;
+    fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), RenderError> {
+        self.0.append_child(parent, child)
+    }
 
-// pub struct Week;
+    fn add_listener(&mut self, node: NodeId, event: &str, listener: Listener) -> Result<(), RenderError> {
+        self.0.add_listener(node, event, listener)
+    }
+}
+
+impl<I: FormInput> FormInput for Optional<I> {
+    type Output = Option<I::Output>;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        if self.input.is_empty(&value) {
+            Ok(None)
+        } else {
+            self.input.parse(value).await.map(Some)
+        }
+    }
+
+    fn form_value(&self, form_data: &dyn FormSource, name: &str) -> FormValue {
+        self.input.form_value(form_data, name)
+    }
+
+    fn is_empty(&self, value: &FormValue) -> bool {
+        self.input.is_empty(value)
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+        self.input.set_name(form, name);
+    }
+}
+
+/// &lt;input type='checkbox'&gt;
+#[derive(Clone)]
+pub struct Checkbox {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: bool,
+}
+
+impl DomElement for Checkbox {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        ElementBuilder::new("div")
+            .class("form-group")
+            .child(ElementBuilder::new("input")
+                .attribute("type", "checkbox")
+                .attribute("name", self.name)
+                .attribute("id", &*id)
+                .boolean_attribute("checked", self.default))
+            .child(ElementBuilder::new("label")
+                .attribute("for", &*id)
+                .text(&*self.label))
+            .into_fragment(renderer)
+    }
+}
+
+impl FormInput for Checkbox {
+    type Output = bool;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        Ok(value.as_text() == Some("on"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='color'&gt;
+#[derive(Clone)]
+pub struct Color {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<Color3>,
+}
+
+impl DomElement for Color {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let color_picker = ElementBuilder::new("input")
+            .attribute("type", "color")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .optional_attribute("value", self.default.map(Color3::as_css_hex));
+
+        labelled(&id, &self.label, color_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Color {
+    type Output = Color3;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .and_then(Color3::parse_from_hex)
+            .ok_or_else(|| FormError::new(self.name, "color input value was not valid color"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Formats submitted by &lt;input type='datetime-local'&gt;, which includes (fractional) seconds depending on the input's step
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S%.f"];
+
+/// Describes a chrono format string for error messages and placeholders, e.g. "%d/%m/%Y" => "DD/MM/YYYY"
+///
+/// Unpadded numbers are described by a single letter, e.g. "%-d.%-m." => "D.M.", names are described like "MMM" for "%b" or "dddd" for "%A".
+/// Invalid format strings are returned unchanged.
+fn describe_format(format: &str) -> String {
+    let mut description = String::new();
+    for item in StrftimeItems::new(format) {
+        match item {
+            Item::Literal(text) | Item::Space(text) => description.push_str(text),
+            Item::Numeric(numeric, pad) => {
+                let number = match numeric {
+                    Numeric::Year | Numeric::IsoYear => "YYYY",
+                    Numeric::YearDiv100 | Numeric::IsoYearDiv100 => "CC",
+                    Numeric::YearMod100 | Numeric::IsoYearMod100 => "YY",
+                    Numeric::Month => "MM",
+                    Numeric::Day => "DD",
+                    Numeric::Ordinal => "DDD",
+                    Numeric::WeekFromSun | Numeric::WeekFromMon | Numeric::IsoWeek => "WW",
+                    Numeric::NumDaysFromSun | Numeric::WeekdayFromMon => "d",
+                    Numeric::Hour | Numeric::Hour12 => "hh",
+                    Numeric::Minute => "mm",
+                    Numeric::Second => "ss",
+                    Numeric::Nanosecond => "fffffffff",
+                    Numeric::Timestamp => "timestamp",
+                    _ => "?"
+                };
+                match pad {
+                    Pad::None | Pad::Space if number.len() == 2 => description.push_str(&number[..1]),
+                    _ => description.push_str(number)
+                }
+            }
+            Item::Fixed(fixed) => description.push_str(match fixed {
+                Fixed::ShortMonthName => "MMM",
+                Fixed::LongMonthName => "MMMM",
+                Fixed::ShortWeekdayName => "ddd",
+                Fixed::LongWeekdayName => "dddd",
+                Fixed::LowerAmPm => "am",
+                Fixed::UpperAmPm => "AM",
+                // Fractional seconds are optional
+                Fixed::Nanosecond => "",
+                Fixed::Nanosecond3 => ".fff",
+                Fixed::Nanosecond6 => ".ffffff",
+                Fixed::Nanosecond9 => ".fffffffff",
+                Fixed::TimezoneName => "zone",
+                Fixed::TimezoneOffset | Fixed::TimezoneOffsetZ => "+hhmm",
+                Fixed::TimezoneOffsetColon | Fixed::TimezoneOffsetColonZ => "+hh:mm",
+                Fixed::TimezoneOffsetDoubleColon => "+hh:mm:ss",
+                Fixed::TimezoneOffsetTripleColon => "+hh",
+                Fixed::RFC2822 => "ddd, DD MMM YYYY hh:mm:ss +hhmm",
+                Fixed::RFC3339 => "YYYY-MM-DDThh:mm:ss+hh:mm",
+                _ => "?"
+            }),
+            Item::Error => return format.to_string(),
+            _ => {}
+        }
+    }
+    description
+}
+
+thread_local! {
+    /// Input types of which browser support has been checked, see [`supports_input_type`]
+    static INPUT_TYPE_SUPPORT: RefCell<Vec<(&'static str, bool)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether the browser supports an input type, browsers show a text input for types they do not support
+///
+/// Outside of a browser, input types are supported unless set otherwise through [`harness::set_input_type_support`]
+fn supports_input_type(input_type: &'static str) -> bool {
+    INPUT_TYPE_SUPPORT.with(|support| {
+        if let Some((_, supported)) = support.borrow().iter().find(|(checked_type, _)| *checked_type == input_type) {
+            return *supported;
+        }
+        let supported = detect_input_type(input_type);
+        support.borrow_mut().push((input_type, supported));
+        supported
+    })
+}
+
+/// Sets whether an input type is supported, replacing the detected support
+pub(crate) fn set_input_type_support(input_type: &'static str, supported: bool) {
+    INPUT_TYPE_SUPPORT.with(|support| {
+        let mut support = support.borrow_mut();
+        support.retain(|(checked_type, _)| *checked_type != input_type);
+        support.push((input_type, supported));
+    });
+}
+
+/// Browsers reset the type of an input to "text" if they do not support the type that is set
+#[cfg(target_arch = "wasm32")]
+fn detect_input_type(input_type: &str) -> bool {
+    let input = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("input").ok())
+        .map(|input| input.unchecked_into::<HtmlInputElement>());
+    match input {
+        Some(input) => {
+            input.set_type(input_type);
+            input.type_() == input_type
+        }
+        None => true
+    }
+}
+
+/// Input types are only unsupported in a browser
+#[cfg(not(target_arch = "wasm32"))]
+fn detect_input_type(_input_type: &str) -> bool {
+    true
+}
+
+/// Parses a date or time value, trying the browser's native format before any fallback formats
+///
+/// # Arguments
+///
+/// * `name`: Name of the input, for errors
+/// * `kind`: Kind of value, for errors
+/// * `value`: Submitted value
+/// * `native`: Formats submitted by browsers with a native picker, the first is reported in errors
+/// * `fallback`: Formats accepted if the browser falls back to a text input
+/// * `parse`: Parses a string with a chrono format string
+///
+/// returns: Result<T, FormError>, the error describes the expected formats
+fn parse_with_formats<T>(name: &'static str, kind: &str, value: FormValue, native: &[&str], fallback: &[&str], parse: impl Fn(&str, &str) -> chrono::ParseResult<T>) -> Result<T, FormError> {
+    let expected = || {
+        let formats: Vec<String> = native.iter()
+            .take(1)
+            .chain(fallback)
+            .map(|format| describe_format(format))
+            .collect();
+        FormError::new(name, format!("{} input value was not valid {}, expected {}", kind, kind, formats.join(" or ")))
+    };
+
+    let text = value.as_text()
+        .map(str::trim)
+        .ok_or_else(expected)?;
+    native.iter()
+        .chain(fallback)
+        .find_map(|format| parse(text, format).ok())
+        .ok_or_else(expected)
+}
+
+/// &lt;input type='date'&gt;
+///
+/// Browsers without a date picker are given a text input instead, in which dates may be entered in any of the `formats`
+#[derive(Clone)]
+pub struct Date {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<chrono::NaiveDate>,
+    pub min: Option<chrono::NaiveDate>,
+    pub max: Option<chrono::NaiveDate>,
+    /// Additional chrono format strings accepted from text input, the first is shown as placeholder
+    pub formats: Vec<&'static str>,
+}
+
+impl DomElement for Date {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        // Without a date picker, the text input shows the value in the first format, which is then also described by the placeholder
+        let native = supports_input_type("date");
+        let format = self.formats.first().copied().filter(|_| !native).unwrap_or("%Y-%m-%d");
+        let date_picker = ElementBuilder::new("input")
+            .attribute("type", if native { "date" } else { "text" })
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default_date| default_date.format(format).to_string()))
+            .optional_attribute("min", self.min.filter(|_| native).map(|minimum_date| minimum_date.format(format).to_string()))
+            .optional_attribute("max", self.max.filter(|_| native).map(|maximum_date| maximum_date.format(format).to_string()))
+            .optional_attribute("placeholder", self.formats.first().copied().or((!native).then_some(format)).map(describe_format));
+
+        labelled(&id, &self.label, date_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Date {
+    type Output = chrono::NaiveDate;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let date = parse_with_formats(self.name, "date", value, &["%Y-%m-%d"], &self.formats, NaiveDate::parse_from_str)?;
+        check_bounds(self.name, date, self.min, self.max, |date| date.format("%Y-%m-%d").to_string())
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='datetime-local'&gt;
+///
+/// Browsers without a date picker are given a text input instead, in which dates may be entered in any of the `formats`
+#[derive(Clone)]
+pub struct DateTime {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<chrono::NaiveDateTime>,
+    pub min: Option<chrono::NaiveDateTime>,
+    pub max: Option<chrono::NaiveDateTime>,
+    /// Additional chrono format strings accepted from text input, the first is shown as placeholder
+    pub formats: Vec<&'static str>,
+}
+
+impl DomElement for DateTime {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        // Without a date picker, the text input shows the value in the first format, which is then also described by the placeholder
+        let native = supports_input_type("datetime-local");
+        let format = self.formats.first().copied().filter(|_| !native).unwrap_or("%Y-%m-%dT%H:%M");
+        let date_picker = ElementBuilder::new("input")
+            .attribute("type", if native { "datetime-local" } else { "text" })
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default_date| default_date.format(format).to_string()))
+            .optional_attribute("min", self.min.filter(|_| native).map(|minimum_date| minimum_date.format(format).to_string()))
+            .optional_attribute("max", self.max.filter(|_| native).map(|maximum_date| maximum_date.format(format).to_string()))
+            .optional_attribute("placeholder", self.formats.first().copied().or((!native).then_some(format)).map(describe_format));
+
+        labelled(&id, &self.label, date_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for DateTime {
+    type Output = chrono::NaiveDateTime;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let datetime = parse_with_formats(self.name, "datetime", value, &DATETIME_FORMATS, &self.formats, NaiveDateTime::parse_from_str)?;
+        check_bounds(self.name, datetime, self.min, self.max, |datetime| datetime.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// UTC offsets offered by [`ZoneSource::Select`], from -12:00 to +14:00 in steps of 15 minutes to include every offset in use
+fn utc_offsets() -> impl Iterator<Item = FixedOffset> {
+    (-720..=840)
+        .step_by(15)
+        .filter_map(|minutes| FixedOffset::east_opt(minutes * 60))
+}
+
+/// Formats a UTC offset in the form of "+HH:MM"
+fn format_offset(offset: FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    format!("{}{:02}:{:02}", sign, seconds.abs() / 3600, seconds.abs() % 3600 / 60)
+}
+
+/// Parses a UTC offset in the form of "+HH:MM"
+fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let (sign, offset) = match (text.strip_prefix('+'), text.strip_prefix('-')) {
+        (Some(offset), _) => (1, offset),
+        (_, Some(offset)) => (-1, offset),
+        _ => return None
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60))
+}
+
+/// Offset of the browser's timezone at a local date and time, which may differ throughout the year due to daylight saving time
+#[cfg(target_arch = "wasm32")]
+fn browser_offset(local: NaiveDateTime) -> Option<FixedOffset> {
+    let date = js_sys::Date::new_with_year_month_day_hr_min_sec(
+        u32::try_from(local.year()).ok()?,
+        local.month0() as i32,
+        local.day() as i32,
+        local.hour() as i32,
+        local.minute() as i32,
+        local.second() as i32,
+    );
+    // getTimezoneOffset is UTC minus local time, in minutes
+    FixedOffset::east_opt(-(date.get_timezone_offset() * 60.0) as i32)
+}
+
+/// The browser's timezone is only available in a browser
+#[cfg(not(target_arch = "wasm32"))]
+fn browser_offset(_local: NaiveDateTime) -> Option<FixedOffset> {
+    None
+}
+
+/// Source of the timezone of a [`ZonedDateTime`] input
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ZoneSource {
+    /// The browser's timezone, at the entered date and time
+    Browser,
+    /// Timezone selected by the user, defaulting to the specified offset
+    Select(FixedOffset),
+}
+
+/// &lt;input type='datetime-local'&gt; with a timezone, yielding the instant the user meant
+///
+/// The UTC offset is submitted as a separate value, named "{name}-offset". It is only rendered as &lt;select&gt; with [`ZoneSource::Select`],
+/// with [`ZoneSource::Browser`] it is a hidden input that is left empty unless there is a default.
+/// Outside of a browser, the offset value must be set for [`ZoneSource::Browser`] inputs.
+#[derive(Clone)]
+pub struct ZonedDateTime {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<chrono::DateTime<FixedOffset>>,
+    /// Earliest allowed instant, checked when parsing
+    pub min: Option<chrono::DateTime<Utc>>,
+    /// Latest allowed instant, checked when parsing
+    pub max: Option<chrono::DateTime<Utc>>,
+    pub zone: ZoneSource,
+}
+
+impl DomElement for ZonedDateTime {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let offset_name = format!("{}-offset", self.name);
+
+        // The offset used to display the default and the limits, which are only displayed if the offset is known
+        let display_offset = match (self.zone, self.default) {
+            (ZoneSource::Select(offset), None) => Some(offset),
+            (_, Some(default)) => Some(*default.offset()),
+            (ZoneSource::Browser, None) => None
+        };
+        let date_picker = ElementBuilder::new("input")
+            .attribute("type", "datetime-local")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default_date| default_date.naive_local().format("%Y-%m-%dT%H:%M").to_string()))
+            .optional_attribute("min", self.min.zip(display_offset).map(|(minimum_date, offset)| minimum_date.with_timezone(&offset).format("%Y-%m-%dT%H:%M").to_string()))
+            .optional_attribute("max", self.max.zip(display_offset).map(|(maximum_date, offset)| maximum_date.with_timezone(&offset).format("%Y-%m-%dT%H:%M").to_string()));
+
+        let zone = match self.zone {
+            ZoneSource::Browser => ElementBuilder::new("input")
+                .attribute("type", "hidden")
+                .attribute("name", &*offset_name)
+                .attribute("value", self.default.map(|default| format_offset(*default.offset())).unwrap_or_default()),
+            ZoneSource::Select(default_offset) => {
+                let selected = self.default.map_or(default_offset, |default| *default.offset());
+                let mut offsets: Vec<FixedOffset> = utc_offsets().collect();
+                if !offsets.contains(&selected) {
+                    offsets.push(selected);
+                    offsets.sort_by_key(FixedOffset::local_minus_utc);
+                }
+                ElementBuilder::new("select")
+                    .attribute("name", &*offset_name)
+                    .attribute("id", format!("{}-offset", id))
+                    .attribute("aria-label", "Timezone")
+                    .children(offsets.into_iter().map(|offset| {
+                        let value = format_offset(offset);
+                        ElementBuilder::new("option")
+                            .attribute("value", &*value)
+                            .boolean_attribute("selected", offset == selected)
+                            .text(format!("UTC{}", value))
+                    }))
+            }
+        };
+
+        labelled(&id, &self.label, date_picker)
+            .child(zone)
+            .into_fragment(renderer)
+    }
+}
+
+impl FormInput for ZonedDateTime {
+    type Output = chrono::DateTime<FixedOffset>;
+
+    /// Expects a list of the local date and time, and the UTC offset. An empty offset is replaced by the browser's timezone
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let (local, offset) = match value {
+            FormValue::List(mut values) if values.len() == 2 => {
+                let offset = values.pop().expect("list must have two values");
+                (values.pop().expect("list must have two values"), offset)
+            }
+            _ => return Err(FormError::new(self.name, "datetime input value was not valid datetime"))
+        };
+        let local = parse_with_formats(self.name, "datetime", local, &DATETIME_FORMATS, &[], NaiveDateTime::parse_from_str)?;
+        let offset = match offset.as_text() {
+            Some("") | None => browser_offset(local),
+            Some(offset) => parse_offset(offset)
+        }.ok_or_else(|| FormError::new(self.name, "timezone was not valid UTC offset"))?;
+
+        let instant = offset.from_local_datetime(&local)
+            .single()
+            .ok_or_else(|| FormError::new(self.name, "datetime input value was not valid datetime"))?;
+        match (self.min, self.max) {
+            (Some(min), _) if instant < min => Err(FormError::new(self.name, format!("value must not be before {}", min.with_timezone(&offset).format("%Y-%m-%d %H:%M")))),
+            (_, Some(max)) if instant > max => Err(FormError::new(self.name, format!("value must not be after {}", max.with_timezone(&offset).format("%Y-%m-%d %H:%M")))),
+            _ => Ok(instant)
+        }
+    }
+
+    /// Reads the local date and time, and the UTC offset
+    fn form_value(&self, form_data: &dyn FormSource, name: &str) -> FormValue {
+        FormValue::List(vec![form_data.get(name), form_data.get(&format!("{}-offset", name))])
+    }
+
+    fn is_empty(&self, value: &FormValue) -> bool {
+        match value {
+            FormValue::List(values) => values.first().and_then(FormValue::as_text).unwrap_or_default().is_empty(),
+            _ => true
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+
+/// &lt;input type='email'&gt;
+#[derive(Clone)]
+pub struct Email {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String,
+}
+
+impl DomElement for Email {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let email = ElementBuilder::new("input")
+            .attribute("type", "email")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", &*self.value)
+            .attribute("autocomplete", "email")
+            .attribute("required", "");
+
+        labelled(&id, &self.label, email).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Email {
+    type Output = EmailAddress;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .map(str::trim)
+            .and_then(EmailAddress::parse)
+            .ok_or_else(|| FormError::new(self.name, "email input value was not valid email address"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Reads a selected file and its metadata
+async fn read_file(name: &'static str, file: FileHandle) -> Result<SelectedFile, FormError> {
+    let bytes = file.read()
+        .await
+        .map_err(|err| FormError::from_js(name, &err))?;
+
+    Ok(SelectedFile {
+        name: file.name(),
+        mime_type: file.mime_type(),
+        size: file.size(),
+        last_modified: file.last_modified(),
+        bytes,
+    })
+}
+
+/// Validates that a single-file input value is a selected file
+fn selected_file(name: &'static str, value: FormValue) -> Result<FileHandle, FormError> {
+    match value {
+        FormValue::File(file) if file.is_unselected() => Err(FormError::new(name, "no file selected")),
+        FormValue::File(file) => Ok(file),
+        FormValue::Missing => Err(FormError::new(name, "no file selected")),
+        _ => Err(FormError::new(name, "file input value was not valid file"))
+    }
+}
+
+/// Whether a file matches an `accept` attribute of comma-separated file extensions and MIME types
+///
+/// An empty `accept` attribute accepts all files
+fn accepts_file(accept: &str, file: &web_sys::File) -> bool {
+    let name = file.name().to_lowercase();
+    let mime_type = file.type_().to_lowercase();
+    let mut patterns = accept.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_lowercase)
+        .peekable();
+
+    patterns.peek().is_none() || patterns.any(|pattern| {
+        if pattern.starts_with('.') {
+            name.ends_with(&*pattern)
+        } else if let Some(media_type) = pattern.strip_suffix("/*") {
+            mime_type.split('/').next() == Some(media_type)
+        } else {
+            mime_type == pattern
+        }
+    })
+}
+
+/// Adds listeners for dropping and pasting files onto an element
+///
+/// Files not matching the `accept` attribute are discarded, the callback is only called if any files remain.
+///
+/// # Arguments
+///
+/// * `zone`: Element onto which files may be dropped
+/// * `accept`: Accepted file types, in the format of the &lt;input&gt; `accept` attribute
+/// * `on_files`: Callback for dropped or pasted files, receiving the zone element and the files
+///
+/// returns: ElementBuilder, the zone with the listeners added
+fn with_drop_listeners<'a>(zone: ElementBuilder<'a>, accept: &'static str, on_files: impl Fn(&HtmlElement, Vec<web_sys::File>) + 'static) -> ElementBuilder<'a> {
+    fn accepted_files(accept: &str, files: Option<FileList>) -> Vec<web_sys::File> {
+        let mut accepted = Vec::new();
+        if let Some(files) = files {
+            for index in 0..files.length() {
+                match files.get(index) {
+                    Some(file) if accepts_file(accept, &file) => accepted.push(file),
+                    _ => {}
+                }
+            }
+        }
+        accepted
+    }
+
+    let on_files = Rc::new(on_files);
+    let drop_callback = on_files.clone();
+    zone.on("dragover", |event: DragEvent| {
+        event.prevent_default();    // Dragover must be cancelled for the drop event to fire
+        let _ = current_target(&event).class_list().add_1("drag-over");
+    })
+        .on("dragleave", |event: Event| {
+            let _ = current_target(&event).class_list().remove_1("drag-over");
+        })
+        .on("drop", move |event: DragEvent| {
+            event.prevent_default();    // Prevent the browser from opening the dropped file
+            let zone = current_target(&event);
+            let _ = zone.class_list().remove_1("drag-over");
+            let files = accepted_files(accept, event.data_transfer().and_then(|transfer| transfer.files()));
+            if !files.is_empty() {
+                drop_callback(&zone, files);
+            }
+        })
+        .on("paste", move |event: ClipboardEvent| {
+            let files = accepted_files(accept, event.clipboard_data().and_then(|transfer| transfer.files()));
+            if !files.is_empty() {
+                event.prevent_default();
+                on_files(&current_target(&event), files);
+            }
+        })
+}
+
+/// Displays the names of files in a drop zone, or a placeholder if there are none
+fn show_file_names(zone: &web_sys::Element, files: &[web_sys::File], placeholder: &str) {
+    if let Ok(Some(display)) = zone.query_selector(".drop-zone-files") {
+        if files.is_empty() {
+            display.set_text_content(Some(placeholder));
+        } else {
+            let names = files.iter()
+                .map(web_sys::File::name)
+                .collect::<Vec<_>>();
+            display.set_text_content(Some(&*names.join(", ")));
+        }
+    }
+}
+
+/// &lt;input type='file'&gt;
+///
+/// Selecting a file is required, use [`Optional`] to allow submitting without a file
+#[derive(Clone)]
+pub struct File {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub accept: &'static str,
+}
+
+impl DomElement for File {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let file_select = ElementBuilder::new("input")
+            .attribute("type", "file")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("accept", self.accept)
+            .attribute("required", "");
+
+        labelled(&id, &self.label, file_select).into_fragment(renderer)
+    }
+}
+
+impl FormInput for File {
+    type Output = SelectedFile;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        read_file(self.name, selected_file(self.name, value)?).await
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// File input onto which a file may be dragged-and-dropped or pasted
+///
+/// Wraps an &lt;input type='file'&gt; which can still be clicked to select a file, yields the same output as [`File`].
+/// Selecting a file is required, use [`Optional`] to allow submitting without a file
+#[derive(Clone)]
+pub struct DropZone {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub accept: &'static str,
+    /// Text displayed in the drop zone while no file is selected
+    pub placeholder: String,
+}
+
+impl DomElement for DropZone {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let placeholder = self.placeholder.clone();
+        let file_select = ElementBuilder::new("input")
+            .attribute("type", "file")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("accept", self.accept)
+            .attribute("required", "")
+            .on("change", move |event: Event| {
+                let input = current_target(&event).unchecked_into::<HtmlInputElement>();
+                DroppedFile::replace(&input, None);
+                let files = input.files()
+                    .and_then(|files| files.get(0))
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                if let Some(zone) = input.parent_element() {
+                    show_file_names(&zone, &files, &placeholder);
+                }
+            });
+        let file_names = ElementBuilder::new("span")
+            .class("drop-zone-files")
+            .text(&*self.placeholder);
+
+        let placeholder = self.placeholder.clone();
+        let zone = ElementBuilder::new("div")
+            .class("drop-zone")
+            .attribute("tabindex", "0")    // Focusable, so that files may be pasted into it
+            .child(file_select)
+            .child(file_names);
+        let zone = with_drop_listeners(zone, self.accept, move |zone, mut files| {
+            files.truncate(1);
+            if let Ok(Some(input)) = zone.query_selector("input[type='file']") {
+                let input = input.unchecked_into::<HtmlInputElement>();
+                // Older browsers cannot construct a DataTransfer, the dropped file is then kept apart from the input.
+                // The input stays empty, so it must no longer be required for the form to be submitted
+                match DataTransfer::new() {
+                    Ok(transfer) => {
+                        for file in &files {
+                            let _ = transfer.items().add_with_file(file);
+                        }
+                        input.set_files(transfer.files().as_ref());
+                        DroppedFile::replace(&input, None);
+                    }
+                    Err(_) => {
+                        input.set_required(false);
+                        DroppedFile::replace(&input, files.first().cloned());
+                    }
+                }
+            }
+            show_file_names(zone, &files, &placeholder);
+        });
+
+        labelled(&id, &self.label, zone).into_fragment(renderer)
+    }
+}
+
+impl FormInput for DropZone {
+    type Output = SelectedFile;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        read_file(self.name, selected_file(self.name, value)?).await
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Shared callback for files dropped onto a [`FileDropArea`]
+///
+/// Cloning the callback is cheap, all clones call the same function.
+pub struct DropCallback {
+    function: Rc<RefCell<dyn FnMut(Vec<SelectedFile>) -> SubmitFuture>>
+}
+
+impl DropCallback {
+    /// Wraps a closure into a drop callback
+    pub fn new(mut function: impl FnMut(Vec<SelectedFile>) + 'static) -> Self {
+        DropCallback {
+            function: Rc::new(RefCell::new(move |files| -> SubmitFuture {
+                function(files);
+                Box::pin(std::future::ready(Ok(())))
+            }))
+        }
+    }
+
+    /// Wraps an asynchronous closure into a drop callback
+    ///
+    /// Errors are converted into a message through their Display implementation, and displayed in the drop area
+    pub fn new_async<F, E>(mut function: impl FnMut(Vec<SelectedFile>) -> F + 'static) -> Self
+        where F: Future<Output=Result<(), E>> + 'static,
+              E: Display
+    {
+        DropCallback {
+            function: Rc::new(RefCell::new(move |files| -> SubmitFuture {
+                let future = function(files);
+                Box::pin(async move { future.await.map_err(|err| err.to_string()) })
+            }))
+        }
+    }
+
+    /// Calls the wrapped closure, the returned future must be awaited for asynchronous callbacks to complete
+    ///
+    /// Panics if called recursively from within the callback itself
+    pub fn call(&self, files: Vec<SelectedFile>) -> SubmitFuture {
+        (self.function.borrow_mut())(files)
+    }
+}
+
+impl Clone for DropCallback {
+    fn clone(&self) -> Self {
+        DropCallback { function: Rc::clone(&self.function) }
+    }
+}
+
+/// Standalone area onto which files may be dragged-and-dropped or pasted, outside of a form
+///
+/// The callback is called with the dropped files that match the `accept` attribute.
+/// Errors reading the files or returned by the callback are displayed inside the area.
+#[derive(Clone)]
+pub struct FileDropArea {
+    pub label: String,
+    pub accept: &'static str,
+    pub on_drop: DropCallback,
+}
+
+impl DomElement for FileDropArea {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let zone = ElementBuilder::new("div")
+            .class("drop-zone")
+            .attribute("tabindex", "0")    // Focusable, so that files may be pasted into it
+            .child(ElementBuilder::new("span")
+                .class("drop-zone-files")
+                .text(&*self.label));
+
+        let label = self.label.clone();
+        let callback = self.on_drop.clone();
+        with_drop_listeners(zone, self.accept, move |zone, files| {
+            show_file_names(zone, &files, &label);
+            show_drop_error(zone, None);
+            let zone = zone.clone();
+            let callback = callback.clone();
+            spawn_local(async move {
+                let mut selected = Vec::with_capacity(files.len());
+                for file in files {
+                    let name = file.name();
+                    match read_file("", FileHandle::from(file)).await {
+                        Ok(file) => selected.push(file),
+                        Err(err) => return show_drop_error(&zone, Some(&*format!("error reading {}: {}", name, err.message)))
+                    }
+                }
+                if let Err(message) = callback.call(selected).await {
+                    show_drop_error(&zone, Some(&*message));
+                }
+            })
+        })
+            .into_fragment(renderer)
+    }
+}
+
+/// Displays an error inside a [`FileDropArea`], replacing any previous error, or removes the error if None
+fn show_drop_error(zone: &HtmlElement, message: Option<&str>) {
+    if let Ok(Some(error)) = zone.query_selector(".form-error") {
+        error.remove();
+    }
+    if let (Some(message), Some(document)) = (message, zone.owner_document()) {
+        if let Ok(error) = document.create_element("span") {
+            let _ = error.set_attribute("class", "form-error");
+            error.set_text_content(Some(message));
+            let _ = zone.append_child(&error);
+        }
+    }
+}
+
+/// &lt;input type='file'&gt;, yielding a reader that reads the file in chunks
+///
+/// Intended for large files, which should not be read into memory at once like [`File`] does.
+/// Selecting a file is required, use [`Optional`] to allow submitting without a file
+#[derive(Clone)]
+pub struct FileStream {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub accept: &'static str,
+    /// Maximum size of each chunk in bytes
+    pub chunk_size: usize,
+}
+
+impl DomElement for FileStream {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let file_select = ElementBuilder::new("input")
+            .attribute("type", "file")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("accept", self.accept)
+            .attribute("required", "");
+
+        labelled(&id, &self.label, file_select).into_fragment(renderer)
+    }
+}
+
+impl FormInput for FileStream {
+    type Output = ChunkedFile;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        Ok(ChunkedFile::new(selected_file(self.name, value)?, self.chunk_size))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='file' multiple&gt;
+///
+/// Yields an empty Vec if no files were selected
+#[derive(Clone)]
+pub struct Files {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub accept: &'static str,
+}
+
+impl DomElement for Files {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let file_select = ElementBuilder::new("input")
+            .attribute("type", "file")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("accept", self.accept)
+            .attribute("multiple", "");
+
+        labelled(&id, &self.label, file_select).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Files {
+    type Output = Vec<SelectedFile>;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let values = match value {
+            FormValue::List(values) => values,
+            FormValue::Missing => Vec::new(),
+            value => vec![value]
+        };
+        let mut files = Vec::with_capacity(values.len());
+        for value in values {
+            match value {
+                FormValue::File(file) if file.is_unselected() => {}
+                FormValue::File(file) => files.push(read_file(self.name, file).await?),
+                _ => return Err(FormError::new(self.name, "file input value was not valid file"))
+            }
+        }
+        Ok(files)
+    }
+
+    /// Reads all files with the input's name
+    fn form_value(&self, form_data: &dyn FormSource, name: &str) -> FormValue {
+        FormValue::List(form_data.get_all(name))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='hidden'&gt;
+///
+/// Not displayed, submits a fixed value
+///
+/// Warning: Hidden values can still be changed by the user, and should be validated like any other input
+#[derive(Clone)]
+pub struct Hidden {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub value: String,
+}
+
+impl DomElement for Hidden {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        ElementBuilder::new("input")
+            .attribute("type", "hidden")
+            .attribute("id", format!("{}-{}", self.form, self.name))
+            .attribute("name", self.name)
+            .attribute("value", &*self.value)
+            .into_fragment(renderer)
+    }
+}
+
+impl FormInput for Hidden {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .map(str::to_string)
+            .ok_or_else(|| FormError::new(self.name, "hidden input value was not string"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+// pub struct Image;   // Use a regular submit
+
+/// Checks a parsed value against the `min` and `max` of its input, as browsers do not enforce them for every input method
+///
+/// # Arguments
+///
+/// * `name`: Name of the input
+/// * `value`: Parsed value
+/// * `min`: Earliest allowed value, if any
+/// * `max`: Latest allowed value, if any
+/// * `format`: Formats the bounds for the error message
+///
+/// returns: Result<T, FormError>
+fn check_bounds<T: PartialOrd>(name: &'static str, value: T, min: Option<T>, max: Option<T>, format: impl Fn(&T) -> String) -> Result<T, FormError> {
+    match (min, max) {
+        (Some(min), _) if value < min => Err(FormError::new(name, format!("value must not be before {}", format(&min)))),
+        (_, Some(max)) if value > max => Err(FormError::new(name, format!("value must not be after {}", format(&max)))),
+        _ => Ok(value)
+    }
+}
+
+/// &lt;input type='month'&gt;
+#[derive(Clone)]
+pub struct Month {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<YearMonth>,
+    pub min: Option<YearMonth>,
+    pub max: Option<YearMonth>,
+}
+
+impl DomElement for Month {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let month_picker = ElementBuilder::new("input")
+            .attribute("type", "month")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default_month| default_month.to_string()))
+            .optional_attribute("min", self.min.map(|minimum_month| minimum_month.to_string()))
+            .optional_attribute("max", self.max.map(|maximum_month| maximum_month.to_string()));
+
+        labelled(&id, &self.label, month_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Month {
+    type Output = YearMonth;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let month = value.as_text()
+            .and_then(YearMonth::parse)
+            .ok_or_else(|| FormError::new(self.name, "month input value was not valid month"))?;
+        check_bounds(self.name, month, self.min, self.max, YearMonth::to_string)
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Numeric types that can be entered through [`Number`] and [`Range`] inputs
+pub trait FormNumber: Copy + PartialOrd + Display + std::str::FromStr + 'static {
+    /// Whether the type only holds whole numbers
+    const INTEGER: bool;
+    /// Smallest value of the type
+    const MIN: Self;
+    /// Largest value of the type
+    const MAX: Self;
+
+    /// Converts a value of the type to f64, rounding to the nearest representable value
+    fn to_f64(self) -> f64;
+
+    /// Converts an f64 to the type, truncating and saturating at its bounds
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! form_number {
+    ($integer:literal: $($number:ty),+) => {
+        $(impl FormNumber for $number {
+            const INTEGER: bool = $integer;
+            const MIN: Self = <$number>::MIN;
+            const MAX: Self = <$number>::MAX;
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $number
+            }
+        })+
+    };
+}
+
+form_number!(true: u8, u16, u32, u64, i8, i16, i32, i64);
+form_number!(false: f32, f64);
+
+/// &lt;input type='number'&gt;
+///
+/// Integer types default to a step of 1, floating point types allow any value if no step is set
+#[derive(Clone)]
+pub struct Number<N: FormNumber = f64> {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<N>,
+    pub min: Option<N>,
+    pub max: Option<N>,
+    pub step: Option<N>,
+}
+
+impl<N: FormNumber> DomElement for Number<N> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let step = match self.step {
+            Some(step) => step.to_string(),
+            None if N::INTEGER => "1".to_string(),
+            None => "any".to_string()
+        };
+        let number = ElementBuilder::new("input")
+            .attribute("type", "number")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default| default.to_string()))
+            .optional_attribute("min", self.min.map(|min| min.to_string()))
+            .optional_attribute("max", self.max.map(|max| max.to_string()))
+            .attribute("step", step);
+
+        labelled(&id, &self.label, number).into_fragment(renderer)
+    }
+}
+
+impl<N: FormNumber> FormInput for Number<N> {
+    type Output = N;
+
+    /// Checks the value against `min` and `max`, and against the bounds of the numeric type
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let text = value.as_text()
+            .map(str::trim)
+            .ok_or_else(|| FormError::new(self.name, "number input value was not number"))?;
+
+        let min = self.min.filter(|min| *min > N::MIN).unwrap_or(N::MIN);
+        let max = self.max.filter(|max| *max < N::MAX).unwrap_or(N::MAX);
+        // Exponent forms such as "1e2" only parse as floating point, so integer types fall back to f64.
+        // The maximum of 64-bit integers rounds up to the next power of two as f64, so integers must be below the value after the maximum
+        let out_of_range = |number: f64| number < N::MIN.to_f64() || if N::INTEGER { number >= N::MAX.to_f64() + 1.0 } else { number > N::MAX.to_f64() };
+        let number = match text.parse::<N>() {
+            Ok(number) => number,
+            Err(_) => match text.parse::<f64>() {
+                Ok(number) if !number.is_finite() => return Err(FormError::new(self.name, "value must be a finite number")),
+                Ok(number) if N::INTEGER && number.fract() != 0.0 => return Err(FormError::new(self.name, "value must be a whole number")),
+                Ok(number) if out_of_range(number) => return Err(FormError::new(self.name, format!("value must be between {} and {}", min, max))),
+                Ok(number) => N::from_f64(number),
+                Err(_) => return Err(FormError::new(self.name, "number input value was not number"))
+            }
+        };
+
+        if !number.to_f64().is_finite() {
+            Err(FormError::new(self.name, "value must be a finite number"))
+        } else if number < min {
+            Err(FormError::new(self.name, format!("value must be at least {}", min)))
+        } else if number > max {
+            Err(FormError::new(self.name, format!("value must be at most {}", max)))
+        } else {
+            Ok(number)
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='password'&gt;
+///
+/// The value is never rendered, so the input is empty every time the form is created.
+/// Entering a password is required, use [`Optional`] to allow submitting without a password
+#[derive(Clone)]
+pub struct Password {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    /// Minimum length in characters, also checked when parsing
+    pub min_length: Option<usize>,
+    /// Maximum length in characters, also checked when parsing
+    pub max_length: Option<usize>,
+}
+
+impl DomElement for Password {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let password = ElementBuilder::new("input")
+            .attribute("type", "password")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("autocomplete", "current-password")
+            .attribute("required", "")
+            .optional_attribute("minlength", self.min_length.map(|min_length| min_length.to_string()))
+            .optional_attribute("maxlength", self.max_length.map(|max_length| max_length.to_string()));
+
+        labelled(&id, &self.label, password).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Password {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let password = value.as_text()
+            .ok_or_else(|| FormError::new(self.name, "password input value was not string"))?;
+        let length = password.chars().count();
+        match (self.min_length, self.max_length) {
+            _ if password.is_empty() => Err(FormError::new(self.name, "password input value was empty")),
+            (Some(min_length), _) if length < min_length => Err(FormError::new(self.name, format!("password is shorter than {} characters", min_length))),
+            (_, Some(max_length)) if length > max_length => Err(FormError::new(self.name, format!("password is longer than {} characters", max_length))),
+            _ => Ok(password.to_string())
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Enum of which the variants are the options of a [`Choice`] input
+///
+/// Can be derived for fieldless enums with `#[derive(ChoiceEnum)]`, which uses the snake_case variant name as value and the variant name as label.
+/// Either can be changed with a `#[choice(value = "...", label = "...", group = "...")]` attribute on the variant, which can also set the option's group.
+pub trait ChoiceEnum: Copy + Eq + 'static {
+    /// All options, in the order they are displayed
+    const VARIANTS: &'static [Self];
+
+    /// Value submitted for this option, must be unique among the options
+    fn value(self) -> &'static str;
+
+    /// Label displayed for this option
+    fn label(self) -> &'static str;
+
+    /// Group this option is displayed in by [`Select`] and [`MultiSelect`], None if it is not grouped
+    ///
+    /// Consecutive options with the same group are placed in the same &lt;optgroup&gt;
+    fn group(self) -> Option<&'static str> {
+        None
+    }
+
+    /// Option with the specified value, None if no option has that value
+    fn from_value(value: &str) -> Option<Self> {
+        Self::VARIANTS.iter()
+            .copied()
+            .find(|variant| variant.value() == value)
+    }
+}
+
+/// &lt;input type='radio'&gt; group, with one option per variant of a [`ChoiceEnum`]
+///
+/// Selecting an option is required if there is no default, use [`Optional`] to allow submitting without a selection
+#[derive(Clone)]
+pub struct Choice<E: ChoiceEnum> {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<E>,
+}
+
+impl<E: ChoiceEnum> DomElement for Choice<E> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let options = E::VARIANTS.iter().flat_map(|variant| {
+            let id = format!("{}-{}-{}", self.form, self.name, variant.value());
+            let radio = ElementBuilder::new("input")
+                .attribute("type", "radio")
+                .attribute("id", &*id)
+                .attribute("name", self.name)
+                .attribute("value", variant.value())
+                .boolean_attribute("checked", self.default == Some(*variant))
+                .boolean_attribute("required", self.default.is_none());
+            let label = ElementBuilder::new("label")
+                .attribute("for", &*id)
+                .text(variant.label());
+            [radio, label]
+        });
+
+        ElementBuilder::new("fieldset")
+            .class("form-group")
+            .child(ElementBuilder::new("legend").text(&*self.label))
+            .children(options)
+            .into_fragment(renderer)
+    }
+}
+
+impl<E: ChoiceEnum> FormInput for Choice<E> {
+    type Output = E;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        match value.as_text() {
+            Some(text) => E::from_value(text).ok_or_else(|| FormError::new(self.name, "not a valid option")),
+            None => Err(FormError::new(self.name, "no option selected"))
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='range'&gt; slider, with the current value displayed next to it
+///
+/// The displayed value is updated while the slider is dragged, tick marks are shown at each value in `ticks`
+#[derive(Clone)]
+pub struct Range<N: FormNumber = f64> {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: N,
+    pub min: N,
+    pub max: N,
+    pub step: N,
+    pub ticks: Vec<N>,
+}
+
+impl<N: FormNumber> DomElement for Range<N> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let slider = ElementBuilder::new("input")
+            .attribute("type", "range")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", self.default.to_string())
+            .attribute("min", self.min.to_string())
+            .attribute("max", self.max.to_string())
+            .attribute("step", self.step.to_string())
+            .on("input", |event: Event| {
+                let slider = current_target(&event);
+                let value = slider.unchecked_ref::<HtmlInputElement>().value();
+                if let Ok(Some(output)) = slider.parent_element().map_or(Ok(None), |group| group.query_selector("output")) {
+                    output.set_text_content(Some(&*value));
+                }
+            });
+        let output = ElementBuilder::new("output")
+            .attribute("for", &*id)
+            .attribute("id", format!("{}-value", id))
+            .text(self.default.to_string());
+
+        if self.ticks.is_empty() {
+            labelled(&id, &self.label, slider)
+                .child(output)
+                .into_fragment(renderer)
+        } else {
+            let list_id = format!("{}-ticks", id);
+            let datalist = ElementBuilder::new("datalist")
+                .attribute("id", &*list_id)
+                .children(self.ticks.iter().map(|tick| ElementBuilder::new("option").attribute("value", tick.to_string())));
+            labelled(&id, &self.label, slider.attribute("list", list_id))
+                .child(output)
+                .child(datalist)
+                .into_fragment(renderer)
+        }
+    }
+}
+
+impl<N: FormNumber> FormInput for Range<N> {
+    type Output = N;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let number = value.as_text()
+            .and_then(|text| text.trim().parse::<N>().ok())
+            .ok_or_else(|| FormError::new(self.name, "range input value was not number"))?;
+        if !number.to_f64().is_finite() {
+            Err(FormError::new(self.name, "value must be a finite number"))
+        } else if number < self.min || number > self.max {
+            Err(FormError::new(self.name, format!("value must be between {} and {}", self.min, self.max)))
+        } else {
+            Ok(number)
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='search'&gt;
+///
+/// Warning: Provides direct user-input String. Subject to XSS risks
+#[derive(Clone)]
+pub struct Search {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String,
+    pub placeholder: String,
+}
+
+impl DomElement for Search {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let search = ElementBuilder::new("input")
+            .attribute("type", "search")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", &*self.value)
+            .optional_attribute("placeholder", Some(&*self.placeholder).filter(|placeholder| !placeholder.is_empty()));
+
+        labelled(&id, &self.label, search).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Search {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .map(str::to_string)
+            .ok_or_else(|| FormError::new(self.name, "search input value was not string"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Appends an &lt;option&gt; for each variant of a [`ChoiceEnum`], grouping them into &lt;optgroup&gt; elements
+fn with_options<'a, E: ChoiceEnum>(mut select: ElementBuilder<'a>, is_selected: impl Fn(E) -> bool) -> ElementBuilder<'a> {
+    let mut current_group: Option<(&'static str, ElementBuilder<'a>)> = None;
+    for variant in E::VARIANTS.iter().copied() {
+        let option = ElementBuilder::new("option")
+            .attribute("value", variant.value())
+            .boolean_attribute("selected", is_selected(variant))
+            .text(variant.label());
+
+        current_group = match (variant.group(), current_group.take()) {
+            (Some(group), Some((current, optgroup))) if group == current => Some((group, optgroup.child(option))),
+            (group, previous) => {
+                if let Some((_, optgroup)) = previous {
+                    select = select.child(optgroup);
+                }
+                match group {
+                    Some(group) => Some((group, ElementBuilder::new("optgroup").attribute("label", group).child(option))),
+                    None => {
+                        select = select.child(option);
+                        None
+                    }
+                }
+            }
+        };
+    }
+    match current_group {
+        Some((_, optgroup)) => select.child(optgroup),
+        None => select
+    }
+}
+
+/// &lt;select&gt; with one option per variant of a [`ChoiceEnum`]
+///
+/// Selecting an option is required if there is no default, use [`Optional`] to allow submitting without a selection
+#[derive(Clone)]
+pub struct Select<E: ChoiceEnum> {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<E>,
+}
+
+impl<E: ChoiceEnum> DomElement for Select<E> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let mut select = ElementBuilder::new("select")
+            .attribute("name", self.name)
+            .attribute("id", &*id);
+        if self.default.is_none() {
+            select = select
+                .attribute("required", "")
+                .child(ElementBuilder::new("option").attribute("value", "").attribute("selected", ""));
+        }
+        let select = with_options(select, |variant| self.default == Some(variant));
+
+        labelled(&id, &self.label, select).into_fragment(renderer)
+    }
+}
+
+impl<E: ChoiceEnum> FormInput for Select<E> {
+    type Output = E;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        match value.as_text() {
+            Some("") | None => Err(FormError::new(self.name, "no option selected")),
+            Some(text) => E::from_value(text).ok_or_else(|| FormError::new(self.name, "not a valid option"))
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;select multiple&gt; with one option per variant of a [`ChoiceEnum`]
+///
+/// Yields the selected options in the order they are submitted, which browsers do in display order, and may be empty
+#[derive(Clone)]
+pub struct MultiSelect<E: ChoiceEnum> {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Vec<E>,
+}
+
+impl<E: ChoiceEnum> DomElement for MultiSelect<E> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let select = ElementBuilder::new("select")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("multiple", "");
+        let select = with_options(select, |variant| self.default.contains(&variant));
+
+        labelled(&id, &self.label, select).into_fragment(renderer)
+    }
+}
+
+impl<E: ChoiceEnum> FormInput for MultiSelect<E> {
+    type Output = Vec<E>;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let values = match value {
+            FormValue::List(values) => values,
+            FormValue::Missing => Vec::new(),
+            value => vec![value]
+        };
+        values.iter()
+            .map(|value| value.as_text()
+                .and_then(E::from_value)
+                .ok_or_else(|| FormError::new(self.name, "not a valid option")))
+            .collect()
+    }
+
+    /// Reads all selected options
+    fn form_value(&self, form_data: &dyn FormSource, name: &str) -> FormValue {
+        FormValue::List(form_data.get_all(name))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='submit'&gt;
+///
+/// Special case in the web_form! macro, and does not need to be added
+///
+/// Also creates the form's status element, in which submission errors are displayed
+#[derive(Clone)]
+pub struct Submit {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub value: String
+}
+
+impl DomElement for Submit {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        ElementBuilder::new("div")
+            .class("form-group")
+            .child(ElementBuilder::new("input")
+                .attribute("type", "submit")
+                .attribute("id", format!("{}-{}", self.form, self.name))
+                .attribute("value", &*self.value))
+            .child(ElementBuilder::new("span")
+                .class("form-status")
+                .attribute("id", format!("{}-status", self.form)))
+            .into_fragment(renderer)
+    }
+}
+
+/// &lt;input type='tel'&gt;
+///
+/// Telephone number formats vary by country, so only the allowed characters are validated: digits, spaces, and "+-()."
+#[derive(Clone)]
+pub struct Telephone {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String,
+}
+
+impl DomElement for Telephone {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let telephone = ElementBuilder::new("input")
+            .attribute("type", "tel")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", &*self.value)
+            .attribute("pattern", "[0-9 +\\(\\)\\.\\-]*[0-9][0-9 +\\(\\)\\.\\-]*")
+            .attribute("autocomplete", "tel")
+            .attribute("required", "");
+
+        labelled(&id, &self.label, telephone).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Telephone {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let number = value.as_text()
+            .map(str::trim)
+            .ok_or_else(|| FormError::new(self.name, "telephone input value was not string"))?;
+        if number.contains(|char: char| char.is_ascii_digit()) && number.chars().all(|char| char.is_ascii_digit() || " +-().".contains(char)) {
+            Ok(number.to_string())
+        } else {
+            Err(FormError::new(self.name, "telephone input value was not valid telephone number"))
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='text'&gt;
+///
+/// Warning: Provides direct user-input String. Subject to XSS risks
+#[derive(Clone)]
+pub struct Text {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String
+}
+
+impl DomElement for Text {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let text = ElementBuilder::new("input")
+            .attribute("type", "text")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", &*self.value);
+
+        labelled(&id, &self.label, text).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Text {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .map(str::to_string)
+            .ok_or_else(|| FormError::new(self.name, "text input value was not string"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;textarea&gt;, for multi-line text such as pasted data or source code
+///
+/// Warning: Provides direct user-input String. Subject to XSS risks
+#[derive(Clone)]
+pub struct TextArea {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String,
+    pub placeholder: String,
+    /// Visible number of lines, browser default if None
+    pub rows: Option<u32>,
+    /// Visible width in characters, browser default if None
+    pub cols: Option<u32>,
+    /// Maximum length in characters, also checked when parsing
+    pub max_length: Option<usize>,
+    /// Displays the text in a monospace font
+    pub monospace: bool,
+    /// Converts the "\r\n" line endings browsers submit to "\n"
+    pub normalize_newlines: bool,
+}
+
+impl DomElement for TextArea {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let mut text_area = ElementBuilder::new("textarea")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .text(&*self.value)
+            .optional_attribute("placeholder", Some(&*self.placeholder).filter(|placeholder| !placeholder.is_empty()))
+            .optional_attribute("rows", self.rows.map(|rows| rows.to_string()))
+            .optional_attribute("cols", self.cols.map(|cols| cols.to_string()))
+            .optional_attribute("maxlength", self.max_length.map(|max_length| max_length.to_string()));
+        if self.monospace {
+            text_area = text_area.class("monospace");
+        }
+
+        labelled(&id, &self.label, text_area).into_fragment(renderer)
+    }
+}
+
+impl FormInput for TextArea {
+    type Output = String;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let text = value.as_text()
+            .ok_or_else(|| FormError::new(self.name, "textarea value was not string"))?;
+        let text = if self.normalize_newlines {
+            text.replace("\r\n", "\n")
+        } else {
+            text.to_string()
+        };
+
+        // Line endings count as a single character either way, as they do for the maxlength attribute
+        let length = text.chars().count() - text.matches("\r\n").count();
+        match self.max_length {
+            Some(max_length) if length > max_length => Err(FormError::new(self.name, format!("text is longer than {} characters", max_length))),
+            _ => Ok(text)
+        }
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='time'&gt;, named to avoid confusion with time types
+#[derive(Clone)]
+pub struct ClockTime {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<chrono::NaiveTime>,
+    pub min: Option<chrono::NaiveTime>,
+    pub max: Option<chrono::NaiveTime>,
+    /// Step in seconds, the browser default of 60 seconds if None. Steps below 60 seconds allow seconds to be entered
+    pub step: Option<u32>,
+}
+
+impl DomElement for ClockTime {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let time_picker = ElementBuilder::new("input")
+            .attribute("type", "time")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(|default_time| default_time.format("%H:%M:%S").to_string()))
+            .optional_attribute("min", self.min.map(|minimum_time| minimum_time.format("%H:%M:%S").to_string()))
+            .optional_attribute("max", self.max.map(|maximum_time| maximum_time.format("%H:%M:%S").to_string()))
+            .optional_attribute("step", self.step.map(|step| step.to_string()));
+
+        labelled(&id, &self.label, time_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for ClockTime {
+    type Output = chrono::NaiveTime;
+
+    /// Accepts times with or without seconds, as browsers omit seconds if they are zero
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        let text = value.as_text()
+            .ok_or_else(|| FormError::new(self.name, "time input value was not valid time"))?;
+        let time = NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+            .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M"))
+            .map_err(|_| FormError::new(self.name, "time input value was not valid time"))?;
+        check_bounds(self.name, time, self.min, self.max, |time| time.format("%H:%M:%S").to_string())
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// &lt;input type='url'&gt;, accepting http and https URLs as [`data::Url::parse`] does
+#[derive(Clone)]
+pub struct Url {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub value: String,
+    pub placeholder: String,
+}
+
+impl DomElement for Url {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let url = ElementBuilder::new("input")
+            .attribute("type", "url")
+            .attribute("id", &*id)
+            .attribute("name", self.name)
+            .attribute("value", &*self.value)
+            .attribute("required", "")
+            .optional_attribute("placeholder", Some(&*self.placeholder).filter(|placeholder| !placeholder.is_empty()));
+
+        labelled(&id, &self.label, url).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Url {
+    type Output = data::Url;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .map(str::trim)
+            .and_then(data::Url::parse)
+            .ok_or_else(|| FormError::new(self.name, "url input value was not valid url"))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
+
+/// Formats an ISO week in the form of "YYYY-Www", used by &lt;input type='week'&gt;
+fn format_iso_week(week: IsoWeek) -> String {
+    format!("{:04}-W{:02}", week.year(), week.week())
+}
+
+/// &lt;input type='week'&gt;
+#[derive(Clone)]
+pub struct Week {
+    pub form: &'static str,
+    pub name: &'static str,
+    pub label: String,
+    pub default: Option<IsoWeek>,
+    pub min: Option<IsoWeek>,
+    pub max: Option<IsoWeek>,
+}
+
+impl DomElement for Week {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let id = format!("{}-{}", self.form, self.name);
+        let week_picker = ElementBuilder::new("input")
+            .attribute("type", "week")
+            .attribute("name", self.name)
+            .attribute("id", &*id)
+            .attribute("required", "")
+            .optional_attribute("value", self.default.map(format_iso_week))
+            .optional_attribute("min", self.min.map(format_iso_week))
+            .optional_attribute("max", self.max.map(format_iso_week));
+
+        labelled(&id, &self.label, week_picker).into_fragment(renderer)
+    }
+}
+
+impl FormInput for Week {
+    type Output = IsoWeek;
+
+    async fn parse(&self, value: FormValue) -> Result<Self::Output, FormError> {
+        value.as_text()
+            .and_then(|text| text.split_once("-W"))
+            .and_then(|(year, week)| NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon))
+            .map(|date| date.iso_week())
+            .ok_or_else(|| FormError::new(self.name, "week input value was not valid week"))
+            .and_then(|week| check_bounds(self.name, week, self.min, self.max, |week| format_iso_week(*week)))
+    }
+
+    fn set_name(&mut self, form: &'static str, name: &'static str) {
+        self.form = form;
+        self.name = name;
+    }
+}
 
@@ -5,15 +5,18 @@
 pub mod dom;
 pub mod form;
 pub mod data;
+pub mod download;
 
 /// Module containing applet-initialisation logic
 ///
 /// applet_entrypoint! macro handles all initialisation logic for end users,
 pub mod applet_init {
-    use crate::Applet;
-    pub use web_sys::ShadowRoot;
+    use std::cell::RefCell;
+    use crate::{Applet, AppletHandle};
+    pub use web_sys::{Document, ShadowRoot};
     pub use wasm_bindgen::JsValue;
-    use crate::dom::DomElement;
+    use crate::dom::vdom::{VirtualDom, VNode};
+    use crate::dom::web::WebRenderer;
 
     /// Macro to define entrypoint for applet struct
     ///
@@ -32,6 +35,22 @@ pub mod applet_init {
         };
     }
 
+    thread_local! {
+        /// Handles of initialised applets by their shadowroot, keeping each applet's renderer and event listeners alive
+        static APPLETS: RefCell<Vec<(ShadowRoot, Box<dyn Mounted>)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Applet mounted in a shadowroot, of any applet type
+    trait Mounted {
+        fn unmount(&self) -> Result<(), JsValue>;
+    }
+
+    impl<T: Applet> Mounted for AppletHandle<T> {
+        fn unmount(&self) -> Result<(), JsValue> {
+            AppletHandle::unmount(self)
+        }
+    }
+
     /// Applet initialisation function, generally used indirectly through the applet_entrypoint! macro
     ///
     /// An __applet_entrypoint function matching this function's signature must be exported in the WASM-binary
@@ -39,33 +58,210 @@ pub mod applet_init {
     ///
     /// # Arguments
     ///
-    /// * `root`: Shadowroot in which the applet is loaded
+    /// * `root`: Shadowroot in which the applet is loaded, replacing any applet previously initialised in it
     ///
     /// returns: Result<(), JsValue>
     pub fn init<T: Applet>(root: ShadowRoot) -> Result<(), JsValue> {
         let window = web_sys::window().expect("applet must be initialised within browser window");
         let document = window.document().expect("window must have document");
-        let applet = T::new();
 
-        let style = applet.style().to_nodes(&document)?;
-        let content = applet.content()
-            .to_nodes(&document)?;
+        let previous = APPLETS.with(|applets| {
+            let mut applets = applets.borrow_mut();
+            let index = applets.iter().position(|(applet_root, _)| *applet_root == root);
+            index.map(|index| applets.remove(index))
+        });
+        // Unmounted outside of the registry borrow. Listeners and component state may hold handles to the previous applet,
+        // which would otherwise keep it alive through the renderer and scope it owns
+        if let Some((_, previous)) = previous {
+            previous.unmount()?;
+        }
+        while let Some(child) = root.first_child() {
+            root.remove_child(&child)?;
+        }
+
+        let handle = AppletHandle::new(T::new(), root.clone(), document);
+        APPLETS.with(|applets| applets.borrow_mut().push((root, Box::new(handle.clone()))));
+        handle.render()
+    }
 
-        root.append_child(&*style)?;
-        root.append_child(&*content)?;
+    /// Patches the contents of the applet's shadowroot to match freshly rendered style and content
+    ///
+    /// Only nodes that changed since the previous render are updated, see [`crate::dom::vdom`]
+    ///
+    /// # Arguments
+    ///
+    /// * `applet`: Applet to render
+    /// * `handle`: Handle to the applet, passed on to [`Applet::content`]
+    /// * `renderer`: Renderer the applet's nodes are created with, kept between renders
+    /// * `dom`: Virtual DOM of the applet's shadowroot
+    ///
+    /// returns: Result<(), JsValue>
+    pub(crate) fn render<T: Applet>(applet: &T, handle: &AppletHandle<T>, renderer: &mut WebRenderer, dom: &mut VirtualDom) -> Result<(), JsValue> {
+        let mut nodes = VNode::from_element(&applet.style())?;
+        nodes.extend(VNode::from_element(&*applet.content(handle))?);
+        dom.patch(renderer, nodes)?;
         Ok(())
     }
 }
 
-use crate::dom::{AppletStyle, DomElement};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+use web_sys::{Document, ShadowRoot};
+use wasm_bindgen::JsValue;
+use crate::dom::{AppletStyle, DomElement, Scope};
+use crate::dom::vdom::VirtualDom;
+use crate::dom::web::WebRenderer;
 
 /// Trait for applets
-pub trait Applet {
+///
+/// The applet struct itself holds the applet's state, which is modified through [`Applet::update`].
+pub trait Applet: Sized + 'static {
+    /// Message type used to update the applet's state, see [`AppletHandle::send`]
+    type Message: 'static;
+
     fn new() -> Self;
-    /// HTML-content of the applet. Currently only called once and not refreshed
-    fn content(&self) -> Box<dyn DomElement>;
+    /// Handle a message sent to this applet
+    ///
+    /// returns: true if the state has changed and the applet content must be re-rendered
+    fn update(&mut self, message: Self::Message) -> bool {
+        let _ = message;
+        false
+    }
+    /// HTML-content of the applet. Called on initialisation, and again after each update that changes state
+    ///
+    /// The handle may be cloned into callbacks to send messages to this applet
+    fn content(&self, handle: &AppletHandle<Self>) -> Box<dyn DomElement>;
     /// CSS Style of the element, minimal default provided
     fn style(&self) -> AppletStyle {
         AppletStyle::DEFAULT
     }
-}
\ No newline at end of file
+}
+
+/// Handle to a running applet, used to send messages to it
+///
+/// Cloning the handle is cheap, all clones refer to the same applet.
+pub struct AppletHandle<T: Applet> {
+    runtime: Rc<AppletRuntime<T>>
+}
+
+struct AppletRuntime<T: Applet> {
+    applet: RefCell<T>,
+    queue: RefCell<VecDeque<T::Message>>,
+    renderer: RefCell<WebRenderer>,
+    dom: RefCell<VirtualDom>,
+    /// Root scope of the applet's components
+    scope: Scope,
+    /// Whether a render was requested while the applet was updating or rendering
+    render_requested: Cell<bool>,
+    /// Whether the applet was removed from its shadowroot, after which it is no longer rendered
+    unmounted: Cell<bool>,
+}
+
+impl<T: Applet> AppletHandle<T> {
+    fn new(applet: T, root: ShadowRoot, document: Document) -> Self {
+        let mut renderer = WebRenderer::new(document);
+        let root = renderer.adopt(root.into());
+        AppletHandle {
+            runtime: Rc::new_cyclic(|runtime: &Weak<AppletRuntime<T>>| {
+                // Weak reference, as the runtime owns the scope
+                let runtime = runtime.clone();
+                AppletRuntime {
+                    applet: RefCell::new(applet),
+                    queue: RefCell::new(VecDeque::new()),
+                    renderer: RefCell::new(renderer),
+                    dom: RefCell::new(VirtualDom::new(root)),
+                    scope: Scope::root(move || match runtime.upgrade() {
+                        Some(runtime) => AppletHandle { runtime }.render(),
+                        None => Ok(())
+                    }),
+                    render_requested: Cell::new(false),
+                    unmounted: Cell::new(false),
+                }
+            })
+        }
+    }
+
+    /// Sends a message to the applet, re-rendering the applet's content if its state changed
+    ///
+    /// Messages sent while the applet is updating or rendering are queued, and handled once the current update or render completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: Message passed to [`Applet::update`]
+    ///
+    /// returns: Result<(), JsValue>
+    pub fn send(&self, message: T::Message) -> Result<(), JsValue> {
+        self.runtime.queue.borrow_mut().push_back(message);
+        self.handle_queue()
+    }
+
+    /// Passes queued messages to [`Applet::update`], re-rendering the applet's content if its state changed
+    fn handle_queue(&self) -> Result<(), JsValue> {
+        let mut changed = false;
+        loop {
+            // If the applet is already borrowed, queued messages are picked up by the send or render further up the stack
+            let Ok(mut applet) = self.runtime.applet.try_borrow_mut() else { return Ok(()) };
+            let next = self.runtime.queue.borrow_mut().pop_front();
+            match next {
+                Some(message) => changed |= applet.update(message),
+                None => break
+            }
+        }
+
+        if changed || self.runtime.render_requested.take() {
+            self.render()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Re-renders the applet's content, keeping nodes that did not change
+    ///
+    /// Messages sent while rendering, such as by listeners that fire as nodes are patched, are handled afterwards.
+    /// Renders requested while the applet is updating or rendering, such as by updating component state, are deferred until it completes.
+    pub fn render(&self) -> Result<(), JsValue> {
+        if self.runtime.unmounted.get() {
+            return Ok(());
+        }
+        let borrows = (self.runtime.applet.try_borrow(), self.runtime.renderer.try_borrow_mut(), self.runtime.dom.try_borrow_mut());
+        let (Ok(applet), Ok(mut renderer), Ok(mut dom)) = borrows else {
+            self.runtime.render_requested.set(true);
+            return Ok(());
+        };
+        self.runtime.render_requested.set(false);
+        self.runtime.scope.begin_render();
+        let result = applet_init::render(&*applet, self, &mut renderer, &mut dom);
+        // Ended even if rendering failed, so that the next render starts from a completed one
+        self.runtime.scope.end_render();
+        drop((applet, renderer, dom));
+
+        result?;
+        self.handle_queue()
+    }
+
+    /// Removes the applet's content, dropping its event listeners and the state of its components
+    ///
+    /// Listeners and state may hold handles to the applet, which keep the applet alive until they are dropped.
+    /// The applet is no longer rendered afterwards, messages sent to it are still passed to [`Applet::update`].
+    fn unmount(&self) -> Result<(), JsValue> {
+        self.runtime.unmounted.set(true);
+        let result = self.runtime.dom.borrow_mut().clear(&mut *self.runtime.renderer.borrow_mut());
+        // A render without components drops the state of every component
+        self.runtime.scope.begin_render();
+        self.runtime.scope.end_render();
+        result?;
+        Ok(())
+    }
+
+    /// Root scope for creating components in [`Applet::content`], see [`dom::component`]
+    pub fn scope(&self) -> &Scope {
+        &self.runtime.scope
+    }
+}
+
+impl<T: Applet> Clone for AppletHandle<T> {
+    fn clone(&self) -> Self {
+        AppletHandle { runtime: Rc::clone(&self.runtime) }
+    }
+}
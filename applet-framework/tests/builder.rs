@@ -0,0 +1,77 @@
+use applet_framework::dom::{ElementBuilder, Event};
+use applet_framework::dom::html::{HtmlNode, HtmlRenderer};
+use applet_framework::dom::vdom::KEY_ATTRIBUTE;
+
+fn to_html(element: ElementBuilder) -> String {
+    let mut renderer = HtmlRenderer::new();
+    let root = element.build(&mut renderer).unwrap();
+    renderer.to_html(root)
+}
+
+#[test]
+fn attributes_are_escaped() {
+    let link = ElementBuilder::new("a")
+        .attribute("title", r#"a "b" <c> & 'd'"#)
+        .attribute("href", "/search?q=1&page=2")
+        .text("<b>not bold</b>");
+    assert_eq!(
+        to_html(link),
+        r#"<a title="a &quot;b&quot; &lt;c&gt; &amp; &#39;d&#39;" href="/search?q=1&amp;page=2">&lt;b&gt;not bold&lt;/b&gt;</a>"#
+    );
+}
+
+#[test]
+fn attributes_replace_earlier_values() {
+    let input = ElementBuilder::new("input")
+        .attribute("type", "text")
+        .attribute("value", "first")
+        .attribute("value", "second")
+        .optional_attribute("placeholder", None::<String>)
+        .optional_attribute("maxlength", Some("8"));
+    assert_eq!(to_html(input), r#"<input type="text" value="second" maxlength="8">"#);
+}
+
+#[test]
+fn boolean_attributes() {
+    let checkbox = ElementBuilder::new("input")
+        .attribute("type", "checkbox")
+        .boolean_attribute("checked", true)
+        .boolean_attribute("disabled", false);
+    assert_eq!(to_html(checkbox), r#"<input type="checkbox" checked="">"#);
+}
+
+#[test]
+fn children_from_iterator() {
+    let list = ElementBuilder::new("ul")
+        .class("items")
+        .class("compact")
+        .child(ElementBuilder::new("li").text("first"))
+        .children(["second", "third"].into_iter().map(|item| ElementBuilder::new("li").text(item)))
+        .children(Vec::<ElementBuilder>::new());
+    assert_eq!(to_html(list), r#"<ul class="items compact"><li>first</li><li>second</li><li>third</li></ul>"#);
+}
+
+#[test]
+fn key_sets_key_attribute() {
+    let item = ElementBuilder::new("li").key("item-1").text("first");
+    assert_eq!(to_html(item), format!(r#"<li {}="item-1">first</li>"#, KEY_ATTRIBUTE));
+}
+
+#[test]
+fn into_fragment_wraps_element() {
+    let mut renderer = HtmlRenderer::new();
+    let root = ElementBuilder::new("button")
+        .on("click", |_: Event| {})
+        .text("Go")
+        .into_fragment(&mut renderer)
+        .unwrap();
+    let button = match renderer.node(root) {
+        HtmlNode::Fragment(children) => {
+            assert_eq!(children.len(), 1);
+            children[0]
+        }
+        node => panic!("expected fragment, got {:?}", node)
+    };
+    assert!(matches!(renderer.node(button), HtmlNode::Element { tag, listeners, .. } if tag == "button" && listeners == &["click"]));
+    assert_eq!(renderer.to_html(root), "<button>Go</button>");
+}
@@ -0,0 +1,124 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use applet_framework::dom::{Children, Component, DomElement, ElementBuilder, NodeId, RenderError, Renderer, Scope, State};
+use applet_framework::dom::html::HtmlRenderer;
+
+/// Component displaying its label and counter, exposing its state to the test
+struct Counter {
+    label: &'static str,
+    states: Rc<RefCell<Vec<State<u32>>>>,
+}
+
+impl Component for Counter {
+    type State = u32;
+
+    fn render(&self, state: &State<u32>, _children: &Children, _scope: &Scope, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        self.states.borrow_mut().push(state.clone());
+        ElementBuilder::new("span")
+            .text(format!("{}={}", self.label, *state.get()))
+            .into_fragment(renderer)
+    }
+}
+
+/// Renders components created by `content` as an applet would, returning the HTML
+fn render(scope: &Scope, content: impl Fn(&Scope) -> Vec<Box<dyn DomElement>>) -> String {
+    scope.begin_render();
+    let elements = content(scope);
+    let mut renderer = HtmlRenderer::new();
+    let html = elements.iter()
+        .map(|element| {
+            let root = element.to_nodes(&mut renderer).unwrap();
+            renderer.to_html(root)
+        })
+        .collect();
+    scope.end_render();
+    html
+}
+
+fn counter(scope: &Scope, label: &'static str, states: &Rc<RefCell<Vec<State<u32>>>>) -> Box<dyn DomElement> {
+    Box::new(scope.component(Counter { label, states: states.clone() }))
+}
+
+fn keyed_counter(scope: &Scope, label: &'static str, states: &Rc<RefCell<Vec<State<u32>>>>) -> Box<dyn DomElement> {
+    Box::new(scope.component(Counter { label, states: states.clone() }).key(label))
+}
+
+#[test]
+fn state_is_kept_between_renders() {
+    let scope = Scope::new();
+    let states = Rc::new(RefCell::new(Vec::new()));
+    assert_eq!(render(&scope, |scope| vec![counter(scope, "a", &states)]), "<span>a=0</span>");
+    states.borrow()[0].set(3).unwrap();
+    assert_eq!(render(&scope, |scope| vec![counter(scope, "a", &states)]), "<span>a=3</span>");
+}
+
+#[test]
+fn state_is_dropped_when_component_is_not_rendered() {
+    let scope = Scope::new();
+    let states = Rc::new(RefCell::new(Vec::new()));
+    render(&scope, |scope| vec![counter(scope, "a", &states)]);
+    states.borrow()[0].set(3).unwrap();
+    render(&scope, |_| vec![]);
+    assert_eq!(render(&scope, |scope| vec![counter(scope, "a", &states)]), "<span>a=0</span>");
+}
+
+#[test]
+fn unkeyed_components_are_identified_by_position() {
+    let scope = Scope::new();
+    let states = Rc::new(RefCell::new(Vec::new()));
+    render(&scope, |scope| vec![counter(scope, "a", &states), counter(scope, "b", &states)]);
+    states.borrow()[0].set(1).unwrap();
+    states.borrow()[1].set(2).unwrap();
+    // Without keys, removing the first component gives its state to the second
+    assert_eq!(render(&scope, |scope| vec![counter(scope, "b", &states)]), "<span>b=1</span>");
+}
+
+#[test]
+fn keyed_components_keep_state_when_reordered() {
+    let scope = Scope::new();
+    let states = Rc::new(RefCell::new(Vec::new()));
+    render(&scope, |scope| vec![keyed_counter(scope, "a", &states), keyed_counter(scope, "b", &states)]);
+    states.borrow()[0].set(1).unwrap();
+    states.borrow()[1].set(2).unwrap();
+    assert_eq!(render(&scope, |scope| vec![keyed_counter(scope, "b", &states), keyed_counter(scope, "a", &states)]), "<span>b=2</span><span>a=1</span>");
+    assert_eq!(render(&scope, |scope| vec![keyed_counter(scope, "b", &states)]), "<span>b=2</span>");
+}
+
+/// Component that increments its state every time it renders
+struct Restless;
+
+impl Component for Restless {
+    type State = u32;
+
+    fn render(&self, state: &State<u32>, _children: &Children, _scope: &Scope, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        let count = *state.get();
+        state.update(|count| *count += 1).unwrap();
+        ElementBuilder::new("span").text(count.to_string()).into_fragment(renderer)
+    }
+}
+
+#[test]
+fn update_during_render_rerenders_once() {
+    let requested = Rc::new(Cell::new(0));
+    let scope = Scope::root({
+        let requested = requested.clone();
+        move || {
+            requested.set(requested.get() + 1);
+            Ok(())
+        }
+    });
+
+    // Renders as an applet does, rendering again while renders are requested
+    let mut outputs = Vec::new();
+    loop {
+        outputs.push(render(&scope, |scope| vec![Box::new(scope.component(Restless))]));
+        if requested.take() == 0 || outputs.len() > 10 {
+            break;
+        }
+    }
+    assert_eq!(outputs, ["<span>0</span>", "<span>1</span>"]);
+
+    // Renders requested from outside a render are not limited
+    assert_eq!(render(&scope, |scope| vec![Box::new(scope.component(Restless))]), "<span>2</span>");
+    assert_eq!(requested.get(), 1);
+}
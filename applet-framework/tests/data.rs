@@ -0,0 +1,16 @@
+use applet_framework::data::Url;
+
+#[test]
+fn url_schemes_default_to_http() {
+    assert_eq!(Url::parse("https://example.com/").map(|url| url.scheme().to_string()), Some("https".into()));
+    assert!(Url::parse("HTTP://example.com/").is_some());
+    assert_eq!(Url::parse("javascript:alert(1)"), None);
+    assert_eq!(Url::parse("data:text/html,<script>alert(1)</script>"), None);
+    assert_eq!(Url::parse("https://example.com/a b"), None);
+}
+
+#[test]
+fn url_schemes_can_be_allowed() {
+    assert!(Url::parse_with_schemes("mailto:ada@example.com", &["mailto"]).is_some());
+    assert_eq!(Url::parse_with_schemes("https://example.com/", &["mailto"]), None);
+}
@@ -0,0 +1,150 @@
+#![allow(incomplete_features)]
+#![feature(async_fn_in_trait)]
+
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveTime, Weekday};
+use applet_framework::data::YearMonth;
+use applet_framework::dom::DomElement;
+use applet_framework::dom::html::{HtmlNode, HtmlRenderer};
+use applet_framework::form::{ClockTime, Date, DateTime, FormError, HTMLForm, Month, WebForm, Week, ZonedDateTime, ZoneSource};
+use applet_framework::form::harness::{self, FormHarness};
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = PeriodForm)]
+struct Period {
+    #[input(Month, label = "Month", default = YearMonth::new(2024, 6), min = YearMonth::new(2024, 1), max = YearMonth::new(2024, 12))]
+    month: YearMonth,
+    #[input(ClockTime, label = "Time", default = None, min = NaiveTime::from_hms_opt(9, 0, 0), max = NaiveTime::from_hms_opt(17, 0, 0), step = None)]
+    time: NaiveTime,
+    #[input(Week, label = "Week", default = None, min = NaiveDate::from_isoywd_opt(2024, 10, Weekday::Mon).map(|date| date.iso_week()), max = None)]
+    week: chrono::IsoWeek,
+}
+
+fn harness() -> FormHarness<PeriodForm> {
+    let mut harness = FormHarness::new(PeriodForm::new(|_| {})).unwrap();
+    harness.set("time", "12:00").set("week", "2024-W20");
+    harness
+}
+
+#[test]
+fn values_within_bounds() {
+    let period = harness().parse().unwrap();
+    assert_eq!(period.month, YearMonth::new(2024, 6).unwrap());
+    assert_eq!(period.time, NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+    assert_eq!(period.week, NaiveDate::from_isoywd_opt(2024, 20, Weekday::Mon).unwrap().iso_week());
+}
+
+#[test]
+fn values_outside_bounds() {
+    let mut harness = harness();
+    harness.set("month", "2025-01").set("time", "08:30").set("week", "2024-W09");
+    assert_eq!(harness.parse(), Err(vec![
+        FormError::new("month", "value must not be after 2024-12"),
+        FormError::new("time", "value must not be before 09:00:00"),
+        FormError::new("week", "value must not be before 2024-W10"),
+    ]));
+}
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = DayForm)]
+struct Day {
+    #[input(Date, label = "Date", default = None, min = NaiveDate::from_ymd_opt(2024, 1, 1), max = None, formats = vec!["%d/%m/%Y"])]
+    date: NaiveDate,
+    #[input(DateTime, label = "Start", default = None, min = None, max = NaiveDate::from_ymd_opt(2024, 1, 1).and_then(|date| date.and_hms_opt(12, 0, 0)), formats = vec![])]
+    start: chrono::NaiveDateTime,
+}
+
+#[test]
+fn text_dates_outside_bounds() {
+    let mut harness = FormHarness::new(DayForm::new(|_| {})).unwrap();
+    harness.set("date", "31/12/2023").set("start", "2024-01-01T12:30");
+    assert_eq!(harness.parse(), Err(vec![
+        FormError::new("date", "value must not be before 2024-01-01"),
+        FormError::new("start", "value must not be after 2024-01-01 12:00"),
+    ]));
+}
+
+#[test]
+fn zone_select_offers_every_offset() {
+    let zoned = ZonedDateTime {
+        form: "meeting",
+        name: "start",
+        label: "Start".into(),
+        default: None,
+        min: None,
+        max: None,
+        zone: ZoneSource::Select(FixedOffset::east_opt(0).unwrap()),
+    };
+    let mut renderer = HtmlRenderer::new();
+    let root = zoned.to_nodes(&mut renderer).unwrap();
+    let options: Vec<String> = renderer.find_all(root, &|node| matches!(node, HtmlNode::Element { tag, .. } if tag == "option"))
+        .into_iter()
+        .map(|option| renderer.attribute(option, "value").unwrap().to_string())
+        .collect();
+    assert_eq!(options.first().map(String::as_str), Some("-12:00"));
+    assert_eq!(options.last().map(String::as_str), Some("+14:00"));
+    for offset in ["-09:30", "-02:30", "+05:45", "+08:45", "+12:45", "+13:45"] {
+        assert!(options.iter().any(|option| option == offset), "missing offset {}", offset);
+    }
+}
+
+fn departure(formats: Vec<&'static str>) -> Date {
+    Date {
+        form: "trip",
+        name: "departure",
+        label: "Departure".into(),
+        default: NaiveDate::from_ymd_opt(2024, 5, 1),
+        min: NaiveDate::from_ymd_opt(2024, 1, 1),
+        max: None,
+        formats,
+    }
+}
+
+fn date_input(date: &Date) -> String {
+    let mut renderer = HtmlRenderer::new();
+    let root = date.to_nodes(&mut renderer).unwrap();
+    let input = renderer.find(root, &|node| matches!(node, HtmlNode::Element { tag, .. } if tag == "input")).unwrap();
+    renderer.to_html(input)
+}
+
+#[test]
+fn text_input_without_date_picker() {
+    harness::set_input_type_support("date", false);
+    assert_eq!(
+        date_input(&departure(vec!["%d/%m/%Y"])),
+        r#"<input type="text" name="departure" id="trip-departure" required="" value="01/05/2024" placeholder="DD/MM/YYYY">"#
+    );
+    assert_eq!(
+        date_input(&departure(vec![])),
+        r#"<input type="text" name="departure" id="trip-departure" required="" value="2024-05-01" placeholder="YYYY-MM-DD">"#
+    );
+
+    harness::set_input_type_support("date", true);
+    assert_eq!(
+        date_input(&departure(vec!["%d/%m/%Y"])),
+        r#"<input type="date" name="departure" id="trip-departure" required="" value="2024-05-01" min="2024-01-01" placeholder="DD/MM/YYYY">"#
+    );
+}
+
+#[test]
+fn formats_are_described() {
+    let placeholder = |format| {
+        let html = date_input(&departure(vec![format]));
+        html.split("placeholder=\"").nth(1).unwrap().trim_end_matches("\">").to_string()
+    };
+    assert_eq!(placeholder("%-d.%-m.%y"), "D.M.YY");
+    assert_eq!(placeholder("%e %b %Y"), "D MMM YYYY");
+    assert_eq!(placeholder("%A, %B %d %C%y"), "dddd, MMMM DD CCYY");
+    assert_eq!(placeholder("%a %j/%G.%V.%u"), "ddd DDD/YYYY.WW.d");
+    assert_eq!(placeholder("%F %I:%M:%S%.3f %p %:z"), "YYYY-MM-DD hh:mm:ss.fff AM +hh:mm");
+    assert_eq!(placeholder("%D %R"), "MM/DD/YY hh:mm");
+    assert_eq!(placeholder("%Y%%%m"), "YYYY%MM");
+}
+
+#[test]
+fn errors_describe_every_format() {
+    let mut harness = FormHarness::new(DayForm::new(|_| {})).unwrap();
+    harness.set("date", "1 May").set("start", "2024-01-01T10:00");
+    assert_eq!(harness.parse(), Err(vec![
+        FormError::new("date", "date input value was not valid date, expected YYYY-MM-DD or DD/MM/YYYY"),
+    ]));
+}
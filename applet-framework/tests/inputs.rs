@@ -0,0 +1,161 @@
+#![allow(incomplete_features)]
+#![feature(async_fn_in_trait)]
+
+use chrono::NaiveDate;
+use applet_framework::dom::DomElement;
+use applet_framework::dom::html::HtmlRenderer;
+use applet_framework::form::{Checkbox, Choice, ChoiceEnum, Date, FormError, Hidden, HTMLForm, Number, Optional, Password, Select, Submit, TextArea, Url, WebForm};
+use applet_framework::form::harness::FormHarness;
+
+fn to_html(element: &dyn DomElement) -> String {
+    let mut renderer = HtmlRenderer::new();
+    let root = element.to_nodes(&mut renderer).unwrap();
+    renderer.to_html(root)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ChoiceEnum)]
+enum Size {
+    Small,
+    #[choice(label = "Extra large", group = "Large")]
+    ExtraLarge,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ChoiceEnum)]
+enum Protocol {
+    HTTP,
+    HTTPSProxy,
+    Http2,
+    WebDAV,
+}
+
+#[test]
+fn acronym_values_are_one_word() {
+    let values: Vec<&str> = Protocol::VARIANTS.iter().map(|protocol| protocol.value()).collect();
+    assert_eq!(values, ["http", "https_proxy", "http2", "web_dav"]);
+    assert_eq!(Protocol::HTTPSProxy.label(), "HTTPSProxy");
+}
+
+#[test]
+fn checkbox() {
+    let checkbox = Checkbox { form: "options", name: "dark", label: "Dark mode".into(), default: true };
+    assert_eq!(to_html(&checkbox), concat!(
+        r#"<div class="form-group"><input type="checkbox" name="dark" id="options-dark" checked="">"#,
+        r#"<label for="options-dark">Dark mode</label></div>"#
+    ));
+}
+
+#[test]
+fn choice() {
+    let choice = Choice::<Size> { form: "order", name: "size", label: "Size".into(), default: None };
+    assert_eq!(to_html(&choice), concat!(
+        r#"<fieldset class="form-group"><legend>Size</legend>"#,
+        r#"<input type="radio" id="order-size-small" name="size" value="small" required=""><label for="order-size-small">Small</label>"#,
+        r#"<input type="radio" id="order-size-extra_large" name="size" value="extra_large" required=""><label for="order-size-extra_large">Extra large</label>"#,
+        r#"</fieldset>"#
+    ));
+}
+
+#[test]
+fn select() {
+    let select = Select { form: "order", name: "size", label: "Size".into(), default: Some(Size::ExtraLarge) };
+    assert_eq!(to_html(&select), concat!(
+        r#"<div class="form-group"><label for="order-size">Size</label><select name="size" id="order-size">"#,
+        r#"<option value="small">Small</option>"#,
+        r#"<optgroup label="Large"><option value="extra_large" selected="">Extra large</option></optgroup>"#,
+        r#"</select></div>"#
+    ));
+}
+
+#[test]
+fn date() {
+    let date = Date {
+        form: "trip",
+        name: "departure",
+        label: "Departure".into(),
+        default: NaiveDate::from_ymd_opt(2024, 5, 1),
+        min: None,
+        max: NaiveDate::from_ymd_opt(2024, 12, 31),
+        formats: vec!["%d/%m/%Y"],
+    };
+    assert_eq!(to_html(&date), concat!(
+        r#"<div class="form-group"><label for="trip-departure">Departure</label>"#,
+        r#"<input type="date" name="departure" id="trip-departure" required="" value="2024-05-01" max="2024-12-31" placeholder="DD/MM/YYYY">"#,
+        r#"</div>"#
+    ));
+}
+
+#[test]
+fn optional_omits_required() {
+    let number = Optional::new("trip", "guests", Number::<u8> { form: "", name: "", label: "Guests".into(), default: None, min: Some(1), max: None, step: None });
+    assert_eq!(to_html(&number), concat!(
+        r#"<div class="form-group"><label for="trip-guests">Guests</label>"#,
+        r#"<input type="number" name="guests" id="trip-guests" min="1" step="1">"#,
+        r#"</div>"#
+    ));
+}
+
+#[test]
+fn text_is_escaped() {
+    let text_area = TextArea {
+        form: "note",
+        name: "body",
+        label: "Body <required>".into(),
+        value: "a < b & \"c\"".into(),
+        placeholder: String::new(),
+        rows: Some(4),
+        cols: None,
+        max_length: None,
+        monospace: false,
+        normalize_newlines: true,
+    };
+    assert_eq!(to_html(&text_area), concat!(
+        r#"<div class="form-group"><label for="note-body">Body &lt;required&gt;</label>"#,
+        r#"<textarea id="note-body" name="body" rows="4">a &lt; b &amp; &quot;c&quot;</textarea>"#,
+        r#"</div>"#
+    ));
+
+    let hidden = Hidden { form: "note", name: "token", value: "\"quoted\"".into() };
+    assert_eq!(to_html(&hidden), r#"<input type="hidden" id="note-token" name="token" value="&quot;quoted&quot;">"#);
+}
+
+#[test]
+fn submit() {
+    let submit = Submit { form: "note", name: "submit", value: "Save".into() };
+    assert_eq!(to_html(&submit), r#"<div class="form-group"><input type="submit" id="note-submit" value="Save"><span id="note-status" class="form-status"></span></div>"#);
+}
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = LoginForm)]
+struct Login {
+    #[input(Password, label = "Password", min_length = None, max_length = None)]
+    password: String,
+    #[input(Optional<Password>, label = "Token", min_length = None, max_length = None)]
+    token: Option<String>,
+    #[input(Optional<Url>, label = "Homepage", value = "", placeholder = "")]
+    homepage: Option<applet_framework::data::Url>,
+}
+
+#[test]
+fn password_is_required() {
+    let password = Password { form: "login", name: "password", label: "Password".into(), min_length: None, max_length: None };
+    assert_eq!(to_html(&password), concat!(
+        r#"<div class="form-group"><label for="login-password">Password</label>"#,
+        r#"<input type="password" id="login-password" name="password" autocomplete="current-password" required="">"#,
+        r#"</div>"#
+    ));
+
+    let mut harness = FormHarness::new(LoginForm::new(|_| {})).unwrap();
+    assert_eq!(harness.parse(), Err(vec![FormError::new("password", "value is required")]));
+    harness.set("password", "hunter2");
+    assert_eq!(harness.parse(), Ok(Login { password: "hunter2".into(), token: None, homepage: None }));
+}
+
+#[test]
+fn url_rejects_scripts() {
+    let mut harness = FormHarness::new(LoginForm::new(|_| {})).unwrap();
+    harness.set("password", "hunter2").set("homepage", "https://example.com/");
+    assert_eq!(harness.parse().unwrap().homepage.unwrap().as_str(), "https://example.com/");
+    harness.set("homepage", "javascript:alert(1)");
+    assert_eq!(harness.parse(), Err(vec![FormError::new("homepage", "url input value was not valid url")]));
+}
@@ -0,0 +1,78 @@
+#![allow(incomplete_features)]
+#![feature(async_fn_in_trait)]
+
+use applet_framework::form::{FormError, HTMLForm, Number, Range, WebForm};
+use applet_framework::form::harness::FormHarness;
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = NumberForm)]
+struct Numbers {
+    #[input(Number<u8>, label = "Byte", default = None, min = None, max = None, step = None)]
+    byte: u8,
+    #[input(Number<f32>, label = "Float", default = None, min = None, max = None, step = None)]
+    float: f32,
+}
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = WideForm)]
+struct Wide {
+    #[input(Number<u64>, label = "Unsigned", default = Some(0), min = None, max = None, step = None)]
+    unsigned: u64,
+    #[input(Number<i64>, label = "Signed", default = Some(0), min = None, max = None, step = None)]
+    signed: i64,
+}
+
+fn parse(byte: &str, float: &str) -> Result<Numbers, Vec<FormError>> {
+    let mut harness = FormHarness::new(NumberForm::new(|_| {})).unwrap();
+    harness.set("byte", byte).set("float", float);
+    harness.parse()
+}
+
+#[test]
+fn exponent_form_parses_as_integer() {
+    assert_eq!(parse("1e2", "2.5"), Ok(Numbers { byte: 100, float: 2.5 }));
+    assert_eq!(parse("2.5e1", "0"), Ok(Numbers { byte: 25, float: 0.0 }));
+}
+
+#[test]
+fn exponent_form_out_of_range() {
+    assert_eq!(parse("1e3", "0"), Err(vec![FormError::new("byte", "value must be between 0 and 255")]));
+    assert_eq!(parse("1.5e0", "0"), Err(vec![FormError::new("byte", "value must be a whole number")]));
+}
+
+#[test]
+fn non_finite_values_are_rejected() {
+    assert_eq!(parse("NaN", "0"), Err(vec![FormError::new("byte", "value must be a finite number")]));
+    assert_eq!(parse("inf", "0"), Err(vec![FormError::new("byte", "value must be a finite number")]));
+    assert_eq!(parse("0", "NaN"), Err(vec![FormError::new("float", "value must be a finite number")]));
+    assert_eq!(parse("0", "-inf"), Err(vec![FormError::new("float", "value must be a finite number")]));
+}
+
+#[derive(WebForm, Debug, PartialEq)]
+#[web_form(form = RangeForm)]
+struct Ranged {
+    #[input(Range<f64>, label = "Opacity", default = 0.5, min = 0.0, max = 1.0, step = 0.1, ticks = vec![])]
+    opacity: f64,
+}
+
+#[test]
+fn range_rejects_non_finite_values() {
+    let mut harness = FormHarness::new(RangeForm::new(|_| {})).unwrap();
+    assert_eq!(harness.parse(), Ok(Ranged { opacity: 0.5 }));
+    harness.set("opacity", "NaN");
+    assert_eq!(harness.parse(), Err(vec![FormError::new("opacity", "value must be a finite number")]));
+    harness.set("opacity", "2");
+    assert_eq!(harness.parse(), Err(vec![FormError::new("opacity", "value must be between 0 and 1")]));
+}
+
+#[test]
+fn wide_integers_beyond_bounds_are_rejected() {
+    let mut harness = FormHarness::new(WideForm::new(|_| {})).unwrap();
+    harness.set("unsigned", "18446744073709551615").set("signed", "-9223372036854775808");
+    assert_eq!(harness.parse(), Ok(Wide { unsigned: u64::MAX, signed: i64::MIN }));
+    harness.set("unsigned", "18446744073709551616").set("signed", "9223372036854775808");
+    assert_eq!(harness.parse(), Err(vec![
+        FormError::new("unsigned", "value must be between 0 and 18446744073709551615"),
+        FormError::new("signed", "value must be between -9223372036854775808 and 9223372036854775807"),
+    ]));
+}
@@ -0,0 +1,96 @@
+#![allow(incomplete_features)]
+#![feature(async_fn_in_trait)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use applet_framework::form::{Checkbox, FormError, HTMLForm, Number, Optional, Text, WebForm};
+use applet_framework::form::harness::{FormHarness, SubmitError};
+
+#[derive(WebForm, Debug, Clone, PartialEq)]
+#[web_form(form = SignupForm, submit = "Sign up")]
+struct Signup {
+    #[input(Text, label = "Name", value = "")]
+    name: String,
+    #[input(Number<u8>, label = "Age", default = 18, min = 13, max = None, step = None)]
+    age: u8,
+    #[input(Checkbox, label = "Subscribe", default = true)]
+    newsletter: bool,
+    #[input(Optional<Text>, label = "Referrer", value = "")]
+    referrer: Option<String>,
+    /// Wrapped input passed whole, its form and name are those of the field
+    #[input(Optional<Number<u8>>, input = Number { form: "", name: "", label: "Siblings".into(), default: None, min: None, max: None, step: None })]
+    siblings: Option<u8>,
+}
+
+/// Harness for a form that stores the data it was submitted with
+fn signup() -> (FormHarness<SignupForm>, Rc<RefCell<Option<Signup>>>) {
+    let submitted = Rc::new(RefCell::new(None));
+    let target = submitted.clone();
+    let harness = FormHarness::new(SignupForm::new(move |signup| *target.borrow_mut() = Some(signup))).unwrap();
+    (harness, submitted)
+}
+
+#[test]
+fn submit_with_defaults() {
+    let (mut harness, submitted) = signup();
+    harness.set("name", "Ada");
+    harness.submit().unwrap();
+    assert_eq!(*submitted.borrow(), Some(Signup { name: "Ada".into(), age: 18, newsletter: true, referrer: None, siblings: None }));
+}
+
+#[test]
+fn submit_changed_values() {
+    let (mut harness, submitted) = signup();
+    harness.set("name", "Ada").set("age", "36").check("newsletter", false).set("referrer", "Charles").set("siblings", "2");
+    harness.submit().unwrap();
+    assert_eq!(*submitted.borrow(), Some(Signup { name: "Ada".into(), age: 36, newsletter: false, referrer: Some("Charles".into()), siblings: Some(2) }));
+}
+
+#[test]
+fn required_inputs_prevent_submission() {
+    let (mut harness, submitted) = signup();
+    harness.clear("age");
+    assert_eq!(harness.submit(), Err(SubmitError::Invalid(vec![FormError::new("age", "value is required")])));
+    assert_eq!(*submitted.borrow(), None);
+}
+
+#[test]
+fn invalid_values_report_every_field() {
+    let (mut harness, submitted) = signup();
+    harness.set("name", "Ada").set("age", "12");
+    let error = harness.submit().unwrap_err();
+    assert_eq!(error, SubmitError::Invalid(vec![FormError::new("age", "value must be at least 13")]));
+    assert_eq!(error.to_string(), "form is invalid; age: value must be at least 13");
+    assert_eq!(*submitted.borrow(), None);
+}
+
+#[test]
+fn callback_errors_are_returned() {
+    let mut harness = FormHarness::new(SignupForm::new_async(|signup: Signup| async move {
+        if signup.name == "root" {
+            Err("name is taken")
+        } else {
+            Ok(())
+        }
+    })).unwrap();
+    harness.set("name", "guest");
+    assert_eq!(harness.submit(), Ok(()));
+    harness.set("name", "root");
+    assert_eq!(harness.submit(), Err(SubmitError::Callback("name is taken".into())));
+}
+
+#[test]
+fn live_mode_receives_changes() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let target = changes.clone();
+    let form = SignupForm::new(|_| {}).on_change(200, move |change| target.borrow_mut().push(change));
+    let mut harness = FormHarness::new(form).unwrap();
+    harness.set("name", "Ada");
+    assert!(harness.change());
+    harness.set("age", "x");
+    assert!(harness.change());
+    assert_eq!(*changes.borrow(), vec![
+        Ok(Signup { name: "Ada".into(), age: 18, newsletter: true, referrer: None, siblings: None }),
+        Err(vec![FormError::new("age", "number input value was not number")]),
+    ]);
+}
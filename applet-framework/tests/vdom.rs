@@ -0,0 +1,202 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use applet_framework::dom::{DomElement, ElementBuilder, Event, NodeId, RenderError, Renderer};
+use applet_framework::dom::html::{HtmlNode, HtmlRenderer};
+use applet_framework::dom::vdom::VirtualDom;
+
+/// Element rendered by a function, so that every render creates new virtual nodes
+struct View<F: Fn() -> ElementBuilder<'static>>(F);
+
+impl<F: Fn() -> ElementBuilder<'static>> DomElement for View<F> {
+    fn to_nodes(&self, renderer: &mut dyn Renderer) -> Result<NodeId, RenderError> {
+        (self.0)().into_fragment(renderer)
+    }
+}
+
+fn list(items: &[(&'static str, &'static str)]) -> View<impl Fn() -> ElementBuilder<'static>> {
+    let items = items.to_vec();
+    View(move || ElementBuilder::new("ul")
+        .children(items.iter().map(|(key, text)| ElementBuilder::new("li").key(*key).text(*text))))
+}
+
+/// Virtual DOM rendering into an HtmlRenderer
+struct Harness {
+    renderer: HtmlRenderer,
+    dom: VirtualDom,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut renderer = HtmlRenderer::new();
+        let root = renderer.create_element("div").unwrap();
+        Harness { renderer, dom: VirtualDom::new(root) }
+    }
+
+    /// Patches the previous render, checking the result against a fresh render of the element
+    fn render(&mut self, element: &dyn DomElement) {
+        self.dom.render(&mut self.renderer, element).unwrap();
+
+        let mut fresh = HtmlRenderer::new();
+        let fresh_root = fresh.create_element("div").unwrap();
+        let nodes = element.to_nodes(&mut fresh).unwrap();
+        fresh.append_child(fresh_root, nodes).unwrap();
+        assert_eq!(self.html(), fresh.to_html(fresh_root));
+    }
+
+    fn html(&self) -> String {
+        self.renderer.to_html(self.dom.root())
+    }
+
+    fn keyed(&self, key: &str) -> NodeId {
+        self.renderer.find_by_attribute(self.dom.root(), "data-key", key)
+            .unwrap_or_else(|| panic!("no element with key {}", key))
+    }
+
+    fn keyed_all(&self, keys: &[&str]) -> Vec<NodeId> {
+        keys.iter().map(|key| self.keyed(key)).collect()
+    }
+
+    fn text(&self, text: &str) -> NodeId {
+        self.renderer.find(self.dom.root(), &|node| matches!(node, HtmlNode::Text(node_text) if node_text == text))
+            .unwrap_or_else(|| panic!("no text node {}", text))
+    }
+}
+
+#[test]
+fn keyed_reorder_keeps_nodes() {
+    let mut harness = Harness::new();
+    harness.render(&list(&[("a", "A"), ("b", "B"), ("c", "C")]));
+    let nodes = harness.keyed_all(&["a", "b", "c"]);
+    harness.render(&list(&[("c", "C"), ("a", "A"), ("b", "B")]));
+    assert_eq!(harness.keyed_all(&["a", "b", "c"]), nodes);
+}
+
+#[test]
+fn keyed_reversal_keeps_nodes() {
+    let mut harness = Harness::new();
+    harness.render(&list(&[("a", "A"), ("b", "B"), ("c", "C"), ("d", "D"), ("e", "E")]));
+    let nodes = harness.keyed_all(&["a", "b", "c", "d", "e"]);
+    harness.render(&list(&[("e", "E"), ("d", "D"), ("c", "C"), ("b", "B"), ("a", "A")]));
+    assert_eq!(harness.keyed_all(&["a", "b", "c", "d", "e"]), nodes);
+}
+
+#[test]
+fn keyed_insert_and_remove() {
+    let mut harness = Harness::new();
+    harness.render(&list(&[("a", "A"), ("b", "B"), ("c", "C")]));
+    let nodes = harness.keyed_all(&["a", "c"]);
+    harness.render(&list(&[("x", "X"), ("a", "A"), ("c", "C"), ("y", "Y")]));
+    assert_eq!(harness.keyed_all(&["a", "c"]), nodes);
+    assert_eq!(harness.renderer.find_by_attribute(harness.dom.root(), "data-key", "b"), None);
+}
+
+#[test]
+fn clear_to_empty_and_refill() {
+    let mut harness = Harness::new();
+    harness.render(&list(&[("a", "A"), ("b", "B")]));
+    let list_node = harness.renderer.find(harness.dom.root(), &|node| matches!(node, HtmlNode::Element { tag, .. } if tag == "ul")).unwrap();
+    harness.render(&list(&[]));
+    assert_eq!(harness.html(), "<div><ul></ul></div>");
+    harness.render(&list(&[("b", "B")]));
+    assert_eq!(harness.renderer.find(harness.dom.root(), &|node| matches!(node, HtmlNode::Element { tag, .. } if tag == "ul")), Some(list_node));
+
+    harness.dom.clear(&mut harness.renderer).unwrap();
+    assert_eq!(harness.html(), "<div></div>");
+}
+
+#[test]
+fn tag_change_replaces_node() {
+    let mut harness = Harness::new();
+    harness.render(&View(|| ElementBuilder::new("p").attribute("id", "message").text("Saved")));
+    let paragraph = harness.renderer.find_by_attribute(harness.dom.root(), "id", "message").unwrap();
+    harness.render(&View(|| ElementBuilder::new("span").attribute("id", "message").text("Saved")));
+    assert_ne!(harness.renderer.find_by_attribute(harness.dom.root(), "id", "message"), Some(paragraph));
+}
+
+#[test]
+fn attribute_removal_keeps_node() {
+    let mut harness = Harness::new();
+    harness.render(&View(|| ElementBuilder::new("input").attribute("id", "name").attribute("disabled", "").class("invalid")));
+    let input = harness.renderer.find_by_attribute(harness.dom.root(), "id", "name").unwrap();
+    harness.render(&View(|| ElementBuilder::new("input").attribute("id", "name").attribute("value", "Ada")));
+    assert_eq!(harness.renderer.find_by_attribute(harness.dom.root(), "id", "name"), Some(input));
+    assert_eq!(harness.html(), r#"<div><input id="name" value="Ada"></div>"#);
+}
+
+#[test]
+fn text_update_keeps_node() {
+    let mut harness = Harness::new();
+    harness.render(&View(|| ElementBuilder::new("p").text("Saving")));
+    let text = harness.text("Saving");
+    harness.render(&View(|| ElementBuilder::new("p").text("Saved")));
+    assert_eq!(harness.text("Saved"), text);
+}
+
+#[test]
+fn mixed_keyed_and_unkeyed_siblings() {
+    let mut harness = Harness::new();
+    harness.render(&View(|| ElementBuilder::new("div")
+        .child(ElementBuilder::new("p").text("intro"))
+        .child(ElementBuilder::new("li").key("a").text("A"))
+        .child(ElementBuilder::new("li").key("b").text("B"))
+        .text("outro")));
+    let keyed = harness.keyed_all(&["a", "b"]);
+    let outro = harness.text("outro");
+    harness.render(&View(|| ElementBuilder::new("div")
+        .child(ElementBuilder::new("li").key("b").text("B"))
+        .child(ElementBuilder::new("p").text("intro, updated"))
+        .child(ElementBuilder::new("li").key("a").text("A"))
+        .text("outro")
+        .child(ElementBuilder::new("p").text("footer"))));
+    assert_eq!(harness.keyed_all(&["a", "b"]), keyed);
+    assert_eq!(harness.text("outro"), outro);
+}
+
+/// Button with a listener holding a clone of `guard`
+fn button<T: 'static>(guard: &Rc<T>) -> View<impl Fn() -> ElementBuilder<'static>> {
+    let guard = guard.clone();
+    View(move || {
+        let guard = guard.clone();
+        ElementBuilder::new("button").on("click", move |_: Event| {
+            let _ = &guard;
+        })
+    })
+}
+
+#[test]
+fn removed_node_releases_listener() {
+    let mut harness = Harness::new();
+    let guard = Rc::new(());
+    harness.render(&button(&guard));
+    assert_eq!(Rc::strong_count(&guard), 2);
+    harness.render(&View(|| ElementBuilder::new("p")));
+    assert_eq!(Rc::strong_count(&guard), 1);
+}
+
+#[test]
+fn patched_node_replaces_listener() {
+    let mut harness = Harness::new();
+    let (first, second) = (Rc::new(()), Rc::new(()));
+    harness.render(&button(&first));
+    harness.render(&button(&second));
+    assert_eq!((Rc::strong_count(&first), Rc::strong_count(&second)), (1, 2));
+}
+
+/// Renderer and virtual DOM owned together, as by a running applet
+struct Runtime {
+    renderer: RefCell<HtmlRenderer>,
+    dom: RefCell<VirtualDom>,
+}
+
+#[test]
+fn clear_releases_handles_held_by_listeners() {
+    let mut renderer = HtmlRenderer::new();
+    let root = renderer.create_element("div").unwrap();
+    let runtime = Rc::new(Runtime { renderer: RefCell::new(renderer), dom: RefCell::new(VirtualDom::new(root)) });
+
+    // Listeners holding a handle to the runtime that owns them form a reference cycle, until the nodes are cleared
+    runtime.dom.borrow_mut().render(&mut *runtime.renderer.borrow_mut(), &button(&runtime)).unwrap();
+    assert_eq!(Rc::strong_count(&runtime), 2);
+    runtime.dom.borrow_mut().clear(&mut *runtime.renderer.borrow_mut()).unwrap();
+    assert_eq!(Rc::strong_count(&runtime), 1);
+}
@@ -0,0 +1,63 @@
+#![allow(incomplete_features)]
+#![feature(async_fn_in_trait)]
+
+use applet_framework::web_form;
+use applet_framework::dom::DomElement;
+use applet_framework::dom::html::HtmlRenderer;
+use applet_framework::form::{ChoiceEnum, Choice, HTMLForm, MultiSelect, Number, Optional, Select, Text};
+use applet_framework::form::harness::FormHarness;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, ChoiceEnum)]
+enum Mode {
+    Light,
+    Dark,
+}
+
+web_form! {
+    SettingsForm(settings) -> Settings {
+        submit = "Save".to_string(),
+        nickname = Optional<Text> {
+            input: Text { form: "", name: "", label: "Nickname".into(), value: String::new() }
+        },
+        volume = Number<u8> {
+            label: "Volume".into(), default: Some(5), min: None, max: Some(10), step: None
+        },
+        mode = Choice<Mode> {
+            label: "Mode".into(), default: Some(Mode::Light)
+        },
+        fallback = Select<Mode> {
+            label: "Fallback".into(), default: None
+        },
+        schedule = MultiSelect<Mode> {
+            label: "Schedule".into(), default: vec![]
+        }
+    }
+}
+
+#[test]
+fn generic_input_types() {
+    let mut harness = FormHarness::new(SettingsForm::new(|_| {})).unwrap();
+    harness.set("fallback", "dark").add("schedule", "light").add("schedule", "dark");
+    let settings = harness.parse().unwrap();
+    assert_eq!(settings.nickname, None);
+    assert_eq!(settings.volume, 5);
+    assert_eq!(settings.mode, Mode::Light);
+    assert_eq!(settings.fallback, Mode::Dark);
+    assert_eq!(settings.schedule, vec![Mode::Light, Mode::Dark]);
+
+    harness.set("nickname", "Ada").set("volume", "11");
+    assert!(harness.parse().is_err());
+    harness.set("volume", "10");
+    let settings = harness.parse().unwrap();
+    assert_eq!(settings.nickname.as_deref(), Some("Ada"));
+    assert_eq!(settings.volume, 10);
+}
+
+#[test]
+fn wrapped_inputs_take_the_wrapper_name() {
+    let mut renderer = HtmlRenderer::new();
+    let root = SettingsForm::new(|_| {}).to_nodes(&mut renderer).unwrap();
+    let nickname = renderer.find_by_attribute(root, "name", "nickname").unwrap();
+    assert_eq!(renderer.attribute(nickname, "id"), Some("settings-nickname"));
+    assert_eq!(renderer.attribute(nickname, "required"), None);
+}